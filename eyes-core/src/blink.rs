@@ -0,0 +1,180 @@
+use crate::rng::Rng;
+
+// Timings roughly match a relaxed human blink.
+const CLOSE_SECS: f32 = 0.07;
+const HOLD_SECS: f32 = 0.04;
+const OPEN_SECS: f32 = 0.13;
+const BLINK_SECS: f32 = CLOSE_SECS + HOLD_SECS + OPEN_SECS;
+
+const MIN_INTERVAL_SECS: f32 = 2.0;
+const MAX_INTERVAL_SECS: f32 = 7.0;
+const LONG_STARE_CHANCE: f32 = 0.12;
+const LONG_STARE_SECS: f32 = 12.0;
+const DOUBLE_BLINK_CHANCE: f32 = 0.15;
+const DOUBLE_BLINK_GAP_SECS: f32 = 0.10;
+
+// A flinch snaps shut, stays squeezed a moment and opens warily.
+const FLINCH_CLOSE_SECS: f32 = 0.04;
+const FLINCH_HOLD_SECS: f32 = 0.25;
+const FLINCH_OPEN_SECS: f32 = 0.35;
+const FLINCH_SECS: f32 = FLINCH_CLOSE_SECS + FLINCH_HOLD_SECS + FLINCH_OPEN_SECS;
+
+/// How far each lid of one eye is closed, `0.0` = open, `1.0` = fully shut.
+///
+/// The upper and lower lids travel different distances (see `LidEdge`), so `1.0` on both
+/// makes them meet slightly below the eye's center.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Lids {
+    pub upper: f32,
+    pub lower: f32,
+}
+
+impl Lids {
+    pub const OPEN: Self = Self { upper: 0.0, lower: 0.0 };
+
+    /// Lids for a plain blink at `closure` (both lids move together).
+    pub fn blink(closure: f32) -> Self {
+        let closure = closure.clamp(0.0, 1.0);
+        Self { upper: closure, lower: closure }
+    }
+
+    /// Combines two lid states by taking the more closed lid of each.
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            upper: self.upper.max(other.upper),
+            lower: self.lower.max(other.lower),
+        }
+    }
+}
+
+/// Schedules blinks at natural, randomized intervals.
+#[derive(Debug)]
+pub struct Blinker {
+    rng: Rng,
+    until_next: f32,
+    /// Seconds since the current blink started, if one is running.
+    elapsed: Option<f32>,
+    double_pending: bool,
+}
+
+impl Blinker {
+    pub fn new() -> Self {
+        let mut rng = Rng::new();
+        let until_next = next_interval(&mut rng);
+        Self {
+            rng,
+            until_next,
+            elapsed: None,
+            double_pending: false,
+        }
+    }
+
+    /// Starts a blink now unless one is already running.
+    pub fn trigger(&mut self) {
+        if self.elapsed.is_none() {
+            self.elapsed = Some(0.0);
+        }
+    }
+
+    pub fn is_blinking(&self) -> bool {
+        self.elapsed.is_some()
+    }
+
+    /// Seconds until the next scheduled blink, `None` while blinking.
+    pub fn next_blink_in(&self) -> Option<f32> {
+        self.elapsed.is_none().then_some(self.until_next.max(0.0))
+    }
+
+    /// Advances the schedule and returns the current closure (`0.0..=1.0`).
+    pub fn tick(&mut self, dt: f32) -> f32 {
+        let dt = dt.max(0.0);
+
+        match self.elapsed {
+            Some(elapsed) => {
+                let elapsed = elapsed + dt;
+                if elapsed >= BLINK_SECS {
+                    self.elapsed = None;
+                    if self.double_pending {
+                        self.double_pending = false;
+                        self.until_next = DOUBLE_BLINK_GAP_SECS;
+                    } else {
+                        self.double_pending = self.rng.chance(DOUBLE_BLINK_CHANCE);
+                        self.until_next = if self.double_pending {
+                            DOUBLE_BLINK_GAP_SECS
+                        } else {
+                            next_interval(&mut self.rng)
+                        };
+                    }
+                    0.0
+                } else {
+                    self.elapsed = Some(elapsed);
+                    closure_at(elapsed, CLOSE_SECS, HOLD_SECS, OPEN_SECS)
+                }
+            }
+            None => {
+                self.until_next -= dt;
+                if self.until_next <= 0.0 {
+                    self.elapsed = Some(0.0);
+                }
+                0.0
+            }
+        }
+    }
+}
+
+fn next_interval(rng: &mut Rng) -> f32 {
+    if rng.chance(LONG_STARE_CHANCE) {
+        rng.range(MAX_INTERVAL_SECS, LONG_STARE_SECS)
+    } else {
+        rng.range(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS)
+    }
+}
+
+/// One eye shutting in reaction to being poked.
+#[derive(Debug, Default)]
+pub struct Flinch {
+    /// Seconds since the flinch started, if one is running.
+    elapsed: Option<f32>,
+}
+
+impl Flinch {
+    /// Starts a flinch now; poking again keeps the eye shut longer.
+    pub fn trigger(&mut self) {
+        self.elapsed = Some(match self.elapsed {
+            Some(elapsed) => elapsed.min(FLINCH_CLOSE_SECS),
+            None => 0.0,
+        });
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.elapsed.is_some()
+    }
+
+    /// Advances the flinch and returns the current closure (`0.0..=1.0`).
+    pub fn tick(&mut self, dt: f32) -> f32 {
+        let Some(elapsed) = self.elapsed.map(|elapsed| elapsed + dt.max(0.0)) else {
+            return 0.0;
+        };
+        if elapsed >= FLINCH_SECS {
+            self.elapsed = None;
+            return 0.0;
+        }
+        self.elapsed = Some(elapsed);
+        closure_at(elapsed, FLINCH_CLOSE_SECS, FLINCH_HOLD_SECS, FLINCH_OPEN_SECS)
+    }
+}
+
+fn closure_at(elapsed: f32, close: f32, hold: f32, open: f32) -> f32 {
+    let closure = if elapsed < close {
+        elapsed / close
+    } else if elapsed < close + hold {
+        1.0
+    } else {
+        1.0 - (elapsed - close - hold) / open
+    };
+    ease_in_out(closure.clamp(0.0, 1.0))
+}
+
+fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
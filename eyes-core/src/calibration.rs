@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::geometry::{Point, Vector};
+
+/// How much of the global cursor track is kept for interpolation.
+const TRACK_SPAN: Duration = Duration::from_millis(500);
+/// Global samples further apart than this don't bracket a local position, unless the cursor
+/// stayed put between them: the path in between is unknown.
+const MAX_GAP: Duration = Duration::from_millis(100);
+/// Local positions still waiting for a later global sample are dropped after this long.
+const PENDING_SPAN: Duration = Duration::from_millis(250);
+/// Offsets the estimate is taken from, newest last.
+const MAX_PAIRS: usize = 32;
+/// Offsets within this distance (logical px) of the median always count as inliers, so a
+/// perfectly consistent track doesn't reject half-pixel rounding.
+const MIN_INLIER_RADIUS: f32 = 1.5;
+/// Beyond this many median absolute deviations from the median, an offset is an outlier.
+const OUTLIER_MADS: f32 = 3.0;
+/// Inliers needed before the count stops limiting the confidence.
+const FULL_CONFIDENCE_PAIRS: usize = 12;
+/// Inlier spread (RMS, logical px) that halves the confidence.
+const SPREAD_SCALE: f32 = 2.0;
+
+/// The offset from local to global cursor positions, as far as it's known.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// Global position minus local position, in logical pixels.
+    pub offset: Vector,
+    /// From 0 (a guess) to 1 (many consistent measurements).
+    pub confidence: f32,
+    /// Measurements the offset was averaged from.
+    pub inliers: usize,
+    /// Measurements rejected as mis-paired.
+    pub outliers: usize,
+}
+
+/// Estimates where the applet sits on its output from the cursor positions it sees while
+/// hovered and the global positions of the same cursor.
+///
+/// The two arrive as separate, unsynchronized events, so each local position is paired with
+/// the global track interpolated to its timestamp, and the offsets of recent pairs are
+/// combined robustly: the median finds the consensus, offsets far from it (a pairing that
+/// went wrong during fast motion) are rejected, and the rest are averaged.
+#[derive(Debug, Default)]
+pub struct Calibrator {
+    /// Global positions in logical pixels, oldest first.
+    track: VecDeque<(Instant, Point)>,
+    /// Local positions not yet bracketed by the global track, oldest first.
+    pending: VecDeque<(Instant, Point)>,
+    offsets: VecDeque<Vector>,
+    estimate: Option<Estimate>,
+}
+
+impl Calibrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current estimate; `None` before the first measurement.
+    pub fn estimate(&self) -> Option<Estimate> {
+        self.estimate
+    }
+
+    /// Forgets everything, e.g. when the applet moved or the scale changed.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Adds a global cursor position (logical pixels, output-relative) seen at `at`. Returns
+    /// the new estimate if this completed any measurements.
+    pub fn push_global(&mut self, at: Instant, position: Point) -> Option<Estimate> {
+        if self.track.back().is_some_and(|&(last, _)| at < last) {
+            // Out of order: the track must stay sorted.
+            return None;
+        }
+        self.track.push_back((at, position));
+        while self.track.front().is_some_and(|&(first, _)| at - first > TRACK_SPAN) {
+            self.track.pop_front();
+        }
+        self.resolve(at)
+    }
+
+    /// Adds the cursor position relative to the applet (logical pixels) seen at `at`. Returns
+    /// the new estimate if this completed a measurement.
+    pub fn push_local(&mut self, at: Instant, position: Point) -> Option<Estimate> {
+        self.pending.push_back((at, position));
+        self.resolve(at)
+    }
+
+    /// Pairs every pending local position the track now brackets.
+    fn resolve(&mut self, now: Instant) -> Option<Estimate> {
+        let mut measured = false;
+        while let Some(&(at, local)) = self.pending.front() {
+            match self.global_at(at) {
+                Some(Lookup::Found(global)) => {
+                    self.offsets.push_back(global - local);
+                    if self.offsets.len() > MAX_PAIRS {
+                        self.offsets.pop_front();
+                    }
+                    measured = true;
+                }
+                Some(Lookup::Later) if now.saturating_duration_since(at) < PENDING_SPAN => break,
+                // Too old to ever be bracketed, or the track around it is unusable.
+                Some(Lookup::Later) | None => {}
+            }
+            self.pending.pop_front();
+        }
+
+        if !measured {
+            return None;
+        }
+        self.estimate = estimate(&self.offsets);
+        self.estimate
+    }
+
+    /// The global position at `at`, interpolated along the track.
+    fn global_at(&self, at: Instant) -> Option<Lookup> {
+        let after = self.track.iter().position(|&(t, _)| t >= at);
+        let Some(after) = after else {
+            return Some(Lookup::Later);
+        };
+        let (t1, p1) = self.track[after];
+        if t1 == at {
+            return Some(Lookup::Found(p1));
+        }
+        // Before the start of the track.
+        let (t0, p0) = *self.track.get(after.checked_sub(1)?)?;
+        let gap = t1 - t0;
+        if gap > MAX_GAP && p0.distance(p1) > 0.5 {
+            return None;
+        }
+        let t = (at - t0).as_secs_f32() / gap.as_secs_f32();
+        Some(Lookup::Found(p0 + (p1 - p0) * t))
+    }
+}
+
+enum Lookup {
+    Found(Point),
+    /// Not reached by the track yet.
+    Later,
+}
+
+/// Median, outlier rejection by median absolute deviation, then the mean of the inliers.
+fn estimate(offsets: &VecDeque<Vector>) -> Option<Estimate> {
+    let consensus = Vector::new(
+        median(offsets.iter().map(|offset| offset.x))?,
+        median(offsets.iter().map(|offset| offset.y))?,
+    );
+    let deviation = |offset: &Vector| (*offset - consensus).length();
+    let mad = median(offsets.iter().map(deviation))?;
+    let radius = (OUTLIER_MADS * mad).max(MIN_INLIER_RADIUS);
+
+    let inliers: Vec<Vector> =
+        offsets.iter().copied().filter(|offset| deviation(offset) <= radius).collect();
+    let count = inliers.len();
+    // At least half the offsets are within the median absolute deviation, so never empty.
+    let sum = inliers.iter().fold(Vector::ZERO, |sum, &offset| sum + offset);
+    let offset = sum * (1.0 / count as f32);
+    let spread = (inliers.iter().map(|&inlier| (inlier - offset).length().powi(2)).sum::<f32>()
+        / count as f32)
+        .sqrt();
+
+    let enough = (count as f32 / FULL_CONFIDENCE_PAIRS as f32).min(1.0);
+    let agreement = count as f32 / offsets.len() as f32;
+    let precision = 1.0 / (1.0 + spread / SPREAD_SCALE);
+    Some(Estimate {
+        offset,
+        confidence: enough * agreement * precision,
+        inliers: count,
+        outliers: offsets.len() - count,
+    })
+}
+
+fn median(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let mut values: Vec<f32> = values.collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f32::total_cmp);
+    let middle = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFSET: Vector = Vector::new(1200.0, 4.0);
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    /// A cursor moving fast along x: at 2 px/ms, a pairing 10 ms off is 20 px off.
+    fn global(millis: u64) -> Point {
+        Point::new(1000.0 + 2.0 * millis as f32, 20.0)
+    }
+
+    fn local(millis: u64) -> Point {
+        global(millis) - OFFSET
+    }
+
+    #[test]
+    fn interpolates_the_global_track_to_local_timestamps() {
+        let start = Instant::now();
+        let mut calibrator = Calibrator::new();
+        // Local events land between global samples, which come every 8 ms.
+        for step in 0..20 {
+            calibrator.push_global(ms(start, step * 8), global(step * 8));
+            calibrator.push_local(ms(start, step * 8 + 3), local(step * 8 + 3));
+        }
+        let estimate = calibrator.estimate().unwrap();
+        assert!((estimate.offset - OFFSET).length() < 0.01, "{estimate:?}");
+        assert_eq!(estimate.outliers, 0);
+        assert!(estimate.confidence > 0.9, "{estimate:?}");
+    }
+
+    #[test]
+    fn rejects_mis_paired_samples() {
+        let start = Instant::now();
+        let mut calibrator = Calibrator::new();
+        for step in 0..20 {
+            calibrator.push_global(ms(start, step * 8), global(step * 8));
+            // Every fifth local event carries a stale position.
+            let seen = if step % 5 == 0 { step * 8 } else { step * 8 + 3 };
+            calibrator.push_local(ms(start, step * 8 + 3), local(seen));
+        }
+        let estimate = calibrator.estimate().unwrap();
+        assert!((estimate.offset - OFFSET).length() < 0.01, "{estimate:?}");
+        assert!(estimate.outliers > 0);
+    }
+
+    #[test]
+    fn few_or_scattered_measurements_are_not_confident() {
+        let start = Instant::now();
+        let mut calibrator = Calibrator::new();
+        for step in 0..3 {
+            calibrator.push_global(ms(start, step * 8), global(step * 8));
+            calibrator.push_local(ms(start, step * 8 + 3), local(step * 8 + 3));
+        }
+        assert!(calibrator.estimate().unwrap().confidence < 0.5);
+
+        calibrator.reset();
+        for step in 0..20 {
+            calibrator.push_global(ms(start, step * 8), global(step * 8));
+            let jitter = Vector::new(if step % 2 == 0 { 4.0 } else { -4.0 }, 0.0);
+            calibrator.push_local(ms(start, step * 8 + 3), local(step * 8 + 3) + jitter);
+        }
+        assert!(calibrator.estimate().unwrap().confidence < 0.5);
+    }
+
+    #[test]
+    fn waits_for_the_track_and_skips_gaps() {
+        let start = Instant::now();
+        let mut calibrator = Calibrator::new();
+        calibrator.push_global(ms(start, 0), global(0));
+        // Ahead of the track: measured once the next sample arrives.
+        assert_eq!(calibrator.push_local(ms(start, 4), local(4)), None);
+        assert!(calibrator.push_global(ms(start, 8), global(8)).is_some());
+
+        // The cursor moved during a long silence; where it was in between is unknown.
+        calibrator.push_local(ms(start, 100), local(100));
+        assert_eq!(calibrator.push_global(ms(start, 300), global(300)), None);
+        assert_eq!(calibrator.estimate().unwrap().inliers, 1);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::geometry::Point;
+
+// Pupil size multipliers.
+const NEAR_SCALE: f32 = 0.75;
+const FAR_SCALE: f32 = 1.1;
+const STARTLED_SCALE: f32 = 0.7;
+const DARK_THEME_SCALE: f32 = 1.1;
+
+/// Cursor distances, in eye radii from the middle of the eyes, where the pupils are fully
+/// constricted / fully relaxed.
+const NEAR_RADII: f32 = 2.0;
+const FAR_RADII: f32 = 12.0;
+/// Cursor speed (logical px/s) from which fast motion fully constricts the pupils.
+const FAST_SPEED: f32 = 1500.0;
+
+// Pupils constrict quickly and relax slowly, like real ones (per second).
+const CONSTRICT_RATE: f32 = 8.0;
+const RELAX_RATE: f32 = 1.5;
+const SPEED_SMOOTHING: f32 = 10.0;
+
+/// Closer than this to the target size, the pupils count as resting.
+const SETTLED_SCALE: f32 = 0.002;
+/// Smoothed cursor speed (logical px/s) below which the cursor counts as still.
+const STILL_SPEED: f32 = 1.0;
+
+/// Smoothed pupil size, reacting to how close and how fast the cursor is.
+#[derive(Debug)]
+pub struct Dilation {
+    scale: f32,
+    target: f32,
+    last_cursor: Option<Point>,
+    /// Smoothed cursor speed in logical px/s.
+    speed: f32,
+}
+
+impl Dilation {
+    pub fn new() -> Self {
+        Self {
+            scale: 1.0,
+            target: 1.0,
+            last_cursor: None,
+            speed: 0.0,
+        }
+    }
+
+    /// Current pupil size multiplier.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Whether the size is still changing (or the cursor still moving).
+    pub fn is_changing(&self) -> bool {
+        (self.target - self.scale).abs() > SETTLED_SCALE || self.speed > STILL_SPEED
+    }
+
+    /// Advances toward the size for this frame. `middle` is the point between the eyes.
+    pub fn tick(
+        &mut self,
+        cursor: Option<Point>,
+        middle: Point,
+        eye_radius: f32,
+        dark_theme: bool,
+        dt: f32,
+    ) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        let instant_speed = match (cursor, self.last_cursor) {
+            (Some(now), Some(before)) => now.distance(before) / dt,
+            _ => 0.0,
+        };
+        self.last_cursor = cursor;
+        self.speed += (instant_speed - self.speed) * (1.0 - (-SPEED_SMOOTHING * dt).exp());
+
+        let mut target = match cursor {
+            Some(cursor) => {
+                let radii = cursor.distance(middle) / eye_radius.max(1.0);
+                let t = ((radii - NEAR_RADII) / (FAR_RADII - NEAR_RADII)).clamp(0.0, 1.0);
+                let by_distance = NEAR_SCALE + (FAR_SCALE - NEAR_SCALE) * t;
+                let surprise = (self.speed / FAST_SPEED).clamp(0.0, 1.0);
+                by_distance + (STARTLED_SCALE - by_distance).min(0.0) * surprise
+            }
+            None => 1.0,
+        };
+        if dark_theme {
+            target *= DARK_THEME_SCALE;
+        }
+
+        self.target = target;
+        let rate = if target < self.scale { CONSTRICT_RATE } else { RELAX_RATE };
+        self.scale += (target - self.scale) * (1.0 - (-rate * dt).exp());
+    }
+}
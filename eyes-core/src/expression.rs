@@ -0,0 +1,184 @@
+use crate::blink::Lids;
+
+/// How fast the face moves toward a new expression (per second).
+const TRANSITION_RATE: f32 = 6.0;
+/// A transition ends once no pose value is further than this from the target.
+const SETTLED: f32 = 0.001;
+
+/// Mood shown through the lids, brows and pupils.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Expression {
+    #[default]
+    Neutral,
+    Angry,
+    Surprised,
+    Sleepy,
+    Happy,
+    /// Narrowed, flat lids and lowered brows.
+    Suspicious,
+}
+
+impl Expression {
+    pub const ALL: [Self; 6] = [
+        Self::Neutral,
+        Self::Happy,
+        Self::Surprised,
+        Self::Angry,
+        Self::Suspicious,
+        Self::Sleepy,
+    ];
+
+    fn pose(self) -> Pose {
+        match self {
+            Self::Neutral => Pose::NEUTRAL,
+            Self::Angry => Pose {
+                lids: Lids { upper: 0.3, lower: 0.05 },
+                upper_bow: 0.4,
+                lid_slant: 0.18,
+                brow: 1.0,
+                brow_lift: -0.35,
+                brow_slant: 0.35,
+                pupil_scale: 0.8,
+                ..Pose::NEUTRAL
+            },
+            Self::Surprised => Pose {
+                brow: 1.0,
+                brow_lift: 0.25,
+                brow_arch: 0.15,
+                pupil_scale: 0.7,
+                ..Pose::NEUTRAL
+            },
+            Self::Sleepy => Pose {
+                lids: Lids { upper: 0.45, lower: 0.12 },
+                upper_bow: 0.6,
+                lid_slant: -0.06,
+                pupil_scale: 1.1,
+                ..Pose::NEUTRAL
+            },
+            Self::Happy => Pose {
+                lids: Lids { upper: 0.08, lower: 0.42 },
+                lower_bow: 3.0,
+                brow: 0.6,
+                brow_lift: 0.15,
+                brow_arch: 0.12,
+                pupil_scale: 1.15,
+                ..Pose::NEUTRAL
+            },
+            Self::Suspicious => Pose {
+                lids: Lids { upper: 0.38, lower: 0.3 },
+                upper_bow: 0.15,
+                lower_bow: 0.15,
+                brow: 1.0,
+                brow_lift: -0.25,
+                brow_slant: 0.08,
+                pupil_scale: 0.85,
+                ..Pose::NEUTRAL
+            },
+        }
+    }
+}
+
+/// What an expression does to the eyes; blended while switching between expressions.
+///
+/// Lengths are in half eye heights. Slants are positive when the inner end (toward the
+/// other eye) is lower.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pose {
+    /// Lid closure held by the expression; blinks and sleep close further.
+    pub lids: Lids,
+    /// Curvature of the lid edges relative to the resting lids.
+    pub upper_bow: f32,
+    pub lower_bow: f32,
+    /// Tilt of the upper lid edge.
+    pub lid_slant: f32,
+    /// Brow opacity; no brows are drawn at `0.0`.
+    pub brow: f32,
+    /// Brow height above its resting place just over the eye (negative lowers it).
+    pub brow_lift: f32,
+    pub brow_slant: f32,
+    /// How much the middle of the brow rises above its ends.
+    pub brow_arch: f32,
+    pub pupil_scale: f32,
+}
+
+impl Pose {
+    pub const NEUTRAL: Self = Self {
+        lids: Lids::OPEN,
+        upper_bow: 1.0,
+        lower_bow: 1.0,
+        lid_slant: 0.0,
+        brow: 0.0,
+        brow_lift: 0.0,
+        brow_slant: 0.0,
+        brow_arch: 0.0,
+        pupil_scale: 1.0,
+    };
+
+    fn values(&self) -> [f32; 10] {
+        [
+            self.lids.upper,
+            self.lids.lower,
+            self.upper_bow,
+            self.lower_bow,
+            self.lid_slant,
+            self.brow,
+            self.brow_lift,
+            self.brow_slant,
+            self.brow_arch,
+            self.pupil_scale,
+        ]
+    }
+
+    fn lerp(self, to: Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            lids: Lids {
+                upper: mix(self.lids.upper, to.lids.upper),
+                lower: mix(self.lids.lower, to.lids.lower),
+            },
+            upper_bow: mix(self.upper_bow, to.upper_bow),
+            lower_bow: mix(self.lower_bow, to.lower_bow),
+            lid_slant: mix(self.lid_slant, to.lid_slant),
+            brow: mix(self.brow, to.brow),
+            brow_lift: mix(self.brow_lift, to.brow_lift),
+            brow_slant: mix(self.brow_slant, to.brow_slant),
+            brow_arch: mix(self.brow_arch, to.brow_arch),
+            pupil_scale: mix(self.pupil_scale, to.pupil_scale),
+        }
+    }
+}
+
+/// The expression being shown and the pose on the way to it.
+#[derive(Debug)]
+pub struct Face {
+    expression: Expression,
+    pose: Pose,
+}
+
+impl Face {
+    pub fn new() -> Self {
+        Self {
+            expression: Expression::Neutral,
+            pose: Pose::NEUTRAL,
+        }
+    }
+
+    /// Starts moving toward `expression`; the change is animated by `tick`.
+    pub fn set(&mut self, expression: Expression) {
+        self.expression = expression;
+    }
+
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        let target = self.expression.pose().values();
+        self.pose.values().iter().zip(target).any(|(a, b)| (a - b).abs() > SETTLED)
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        let t = 1.0 - (-TRANSITION_RATE * dt.max(0.0)).exp();
+        self.pose = self.pose.lerp(self.expression.pose(), t);
+    }
+}
@@ -0,0 +1,301 @@
+use std::time::Duration;
+
+use crate::blink::{Blinker, Flinch, Lids};
+use crate::dilation::Dilation;
+use crate::expression::{Expression, Face, Pose};
+use crate::geometry::{Point, Size, Vector};
+use crate::idle::{Idle, IdleState};
+use crate::layout::{contain_pupil, layout_and_centers, pupil_offset, Arrangement};
+use crate::settings::Settings;
+use crate::spring::{self, Motion, Spring, Stepper};
+
+/// How far the other eye closes along with a poked one.
+const SYMPATHY_SQUINT: f32 = 0.3;
+
+/// The state of both eyes: where the pupils are, what the lids do and how the eyes feel.
+///
+/// Frontends feed it the cursor with `tick` and draw it from the accessors.
+#[derive(Debug)]
+pub struct Eyes {
+    left_pupil: Motion,
+    right_pupil: Motion,
+    /// Where the pupils are heading, relative to the eye centers.
+    left_target: Vector,
+    right_target: Vector,
+    stepper: Stepper,
+    cursor_known: bool,
+    blinker: Blinker,
+    left_lids: Lids,
+    right_lids: Lids,
+    left_flinch: Flinch,
+    right_flinch: Flinch,
+    idle: Idle,
+    dilation: Dilation,
+    dark_theme: bool,
+    /// Expression set from outside; `None` picks one from the eyes' own state.
+    expression: Option<Expression>,
+    face: Face,
+    /// Scrolled lines not yet turned into an expression change.
+    scroll_lines: f32,
+    /// Current pupil size relative to `Layout::pupil_radius`.
+    pupil_scale: f32,
+    arrangement: Arrangement,
+    settings: Settings,
+}
+
+impl Default for Eyes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Eyes {
+    pub fn new() -> Self {
+        Self {
+            left_pupil: Motion::default(),
+            right_pupil: Motion::default(),
+            left_target: Vector::new(0.0, 0.0),
+            right_target: Vector::new(0.0, 0.0),
+            stepper: Stepper::default(),
+            cursor_known: false,
+            blinker: Blinker::new(),
+            left_lids: Lids::OPEN,
+            right_lids: Lids::OPEN,
+            left_flinch: Flinch::default(),
+            right_flinch: Flinch::default(),
+            idle: Idle::new(),
+            dilation: Dilation::new(),
+            dark_theme: false,
+            expression: None,
+            face: Face::new(),
+            scroll_lines: 0.0,
+            pupil_scale: 1.0,
+            arrangement: Arrangement::Horizontal,
+            settings: Settings::default(),
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Lets the pupils widen a little on a dark theme (if enabled in the settings).
+    pub fn set_dark_theme(&mut self, dark: bool) {
+        self.dark_theme = dark;
+    }
+
+    pub fn arrangement(&self) -> Arrangement {
+        self.arrangement
+    }
+
+    pub fn set_arrangement(&mut self, arrangement: Arrangement) {
+        self.arrangement = arrangement;
+    }
+
+    /// Shows `expression` until it is reset with `None`, which goes back to the automatic
+    /// one. Changes are animated.
+    pub fn set_expression(&mut self, expression: Option<Expression>) {
+        self.expression = expression;
+    }
+
+    /// Expression matching what the eyes are doing on their own.
+    fn automatic_expression(&self) -> Expression {
+        match self.idle.state() {
+            IdleState::Asleep => Expression::Sleepy,
+            // Looking around for the missing cursor.
+            IdleState::Wandering => Expression::Suspicious,
+            IdleState::Awake if self.idle.startled() => Expression::Surprised,
+            IdleState::Awake => Expression::Neutral,
+        }
+    }
+
+    /// Scrolling by `lines` (positive is up) steps through the expressions, with the
+    /// automatic one before the first.
+    pub fn scroll(&mut self, lines: f32) {
+        // Touchpads scroll in small fractions: step once per whole line, restarting when the
+        // direction changes.
+        if self.scroll_lines * lines < 0.0 {
+            self.scroll_lines = 0.0;
+        }
+        self.scroll_lines += lines;
+        while self.scroll_lines >= 1.0 {
+            self.scroll_lines -= 1.0;
+            self.step_expression(1);
+        }
+        while self.scroll_lines <= -1.0 {
+            self.scroll_lines += 1.0;
+            self.step_expression(-1);
+        }
+    }
+
+    fn step_expression(&mut self, step: isize) {
+        let choices = Expression::ALL.len() as isize + 1;
+        let current = self
+            .expression
+            .and_then(|expression| Expression::ALL.iter().position(|&e| e == expression))
+            .map_or(0, |index| index as isize + 1);
+        let next = (current + step).rem_euclid(choices) as usize;
+        self.set_expression(next.checked_sub(1).map(|index| Expression::ALL[index]));
+    }
+
+    /// Whether anything is still moving, so `tick` should keep running every frame.
+    pub fn is_animating(&self) -> bool {
+        self.blinker.is_blinking()
+            || self.left_flinch.is_active()
+            || self.right_flinch.is_active()
+            || self.face.is_transitioning()
+            || self.idle.is_animating(self.settings.sleep_after_secs)
+            || (self.settings.pupil_dilation && self.dilation.is_changing())
+            || !self.left_pupil.is_settled(self.left_target)
+            || !self.right_pupil.is_settled(self.right_target)
+    }
+
+    /// Time until the next scheduled blink or idle change, as of the last `tick`. Until then
+    /// (and without new cursor input) resting eyes don't need to be ticked at all.
+    pub fn next_event_in(&self) -> Option<Duration> {
+        // Closed lids hide blinks.
+        let blink = match self.idle.state() {
+            IdleState::Asleep => None,
+            _ => self.blinker.next_blink_in(),
+        };
+        let idle = self
+            .idle
+            .next_event_in(self.settings.wander_after_secs, self.settings.sleep_after_secs);
+        blink.into_iter().chain(idle).reduce(f32::min).map(Duration::from_secs_f32)
+    }
+
+    /// Blinks both eyes now (e.g. when the cursor enters the applet).
+    pub fn blink(&mut self) {
+        self.blinker.trigger();
+    }
+
+    /// Reacts to a click at `position` in a window of `window_size`: a poked eye flinches
+    /// shut, a click between the eyes makes both blink.
+    pub fn poke(&mut self, position: Point, window_size: Size) {
+        let (layout, left_eye_center, right_eye_center) =
+            layout_and_centers(window_size, self.arrangement, &self.settings);
+
+        if layout.outline.depth(position - left_eye_center) >= 0.0 {
+            self.left_flinch.trigger();
+            return;
+        }
+        if layout.outline.depth(position - right_eye_center) >= 0.0 {
+            self.right_flinch.trigger();
+            return;
+        }
+
+        let between = match self.arrangement {
+            Arrangement::Horizontal => {
+                (left_eye_center.x..=right_eye_center.x).contains(&position.x)
+                    && (position.y - left_eye_center.y).abs() <= layout.outline.half_height()
+            }
+            Arrangement::Vertical => {
+                (left_eye_center.y..=right_eye_center.y).contains(&position.y)
+                    && (position.x - left_eye_center.x).abs() <= layout.outline.half_width()
+            }
+        };
+        if between {
+            self.blinker.trigger();
+        }
+    }
+
+    pub fn tick(&mut self, cursor: Option<Point>, window_size: Size, dt: f32) {
+        self.cursor_known = cursor.is_some();
+
+        let sleep_after = self.settings.sleep_after_secs;
+        self.idle.tick(self.cursor_known, dt, self.settings.wander_after_secs, sleep_after);
+
+        let expression = self.expression.unwrap_or_else(|| self.automatic_expression());
+        self.face.set(expression);
+        self.face.tick(dt);
+        let pose = self.face.pose();
+
+        let lids = Lids::blink(self.blinker.tick(dt))
+            .max(self.idle.lids(sleep_after))
+            .max(pose.lids);
+        // A poked eye shuts; the other one squints along a little.
+        let left_flinch = self.left_flinch.tick(dt);
+        let right_flinch = self.right_flinch.tick(dt);
+        self.left_lids = lids.max(Lids::blink(left_flinch.max(right_flinch * SYMPATHY_SQUINT)));
+        self.right_lids = lids.max(Lids::blink(right_flinch.max(left_flinch * SYMPATHY_SQUINT)));
+
+        let (layout, left_eye_center, right_eye_center) =
+            layout_and_centers(window_size, self.arrangement, &self.settings);
+
+        let dilation = if self.settings.pupil_dilation {
+            let middle = Point::new(
+                (left_eye_center.x + right_eye_center.x) / 2.0,
+                (left_eye_center.y + right_eye_center.y) / 2.0,
+            );
+            let dark = self.settings.pupil_theme_response && self.dark_theme;
+            self.dilation.tick(cursor, middle, layout.eye_radius, dark, dt);
+            self.dilation.scale()
+        } else {
+            1.0
+        };
+        self.pupil_scale = dilation * self.idle.pupil_scale() * pose.pupil_scale;
+        let pupil_radius = layout.pupil_radius_at(self.pupil_scale);
+
+        let (left_target, right_target) = match (cursor, self.idle.glance()) {
+            (Some(cursor), _) => (cursor, cursor),
+            // Both eyes glance the same way while looking around on their own.
+            (None, Some(glance)) => {
+                let reach = Vector::new(
+                    glance.x * layout.outline.half_width(),
+                    glance.y * layout.outline.half_height(),
+                );
+                (left_eye_center + reach, right_eye_center + reach)
+            }
+            // If we don't have pointer position, keep pupils centered (not looking inward).
+            (None, None) => (left_eye_center, right_eye_center),
+        };
+
+        let spring =
+            Spring::from_response(self.settings.follow_speed, self.settings.follow_damping);
+        let steps = self.stepper.steps(dt);
+        for (motion, goal, center, target) in [
+            (&mut self.left_pupil, &mut self.left_target, left_eye_center, left_target),
+            (&mut self.right_pupil, &mut self.right_target, right_eye_center, right_target),
+        ] {
+            *goal = pupil_offset(center, target, &layout, pupil_radius);
+            spring::advance(motion, *goal, spring, steps);
+            contain_pupil(motion, &layout, pupil_radius);
+        }
+    }
+
+    /// Whether the cursor position was known at the last `tick`.
+    pub fn cursor_known(&self) -> bool {
+        self.cursor_known
+    }
+
+    /// Pupil positions relative to the eye centers.
+    pub fn left_pupil(&self) -> Vector {
+        self.left_pupil.position
+    }
+
+    pub fn right_pupil(&self) -> Vector {
+        self.right_pupil.position
+    }
+
+    pub fn left_lids(&self) -> Lids {
+        self.left_lids
+    }
+
+    pub fn right_lids(&self) -> Lids {
+        self.right_lids
+    }
+
+    /// Current pupil size relative to `Layout::pupil_radius`.
+    pub fn pupil_scale(&self) -> f32 {
+        self.pupil_scale
+    }
+
+    /// The expression as currently shown, part way through any transition.
+    pub fn pose(&self) -> Pose {
+        self.face.pose()
+    }
+}
@@ -0,0 +1,169 @@
+use crate::geometry::Vector;
+
+use crate::blink::Lids;
+use crate::rng::Rng;
+
+/// Time from the first droop to fully closed lids.
+const FALL_ASLEEP_SECS: f32 = 4.0;
+/// Length of the startled reaction after waking up.
+const STARTLE_SECS: f32 = 0.6;
+/// How much the pupils contract when startled.
+const STARTLE_PUPIL_SCALE: f32 = 0.6;
+
+const MIN_GLANCE_SECS: f32 = 0.6;
+const MAX_GLANCE_SECS: f32 = 2.5;
+const GLANCE_STEP: f32 = 0.9;
+const RECENTER_CHANCE: f32 = 0.25;
+
+/// What the eyes do while no cursor position is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleState {
+    /// Cursor known, or not missing for long.
+    Awake,
+    /// Looking around on their own.
+    Wandering,
+    /// Lids drooping and then closed.
+    Asleep,
+}
+
+#[derive(Debug)]
+pub struct Idle {
+    rng: Rng,
+    cursor_known: bool,
+    /// Seconds since the cursor position was last known.
+    idle_secs: f32,
+    state: IdleState,
+    /// Where the eyes glance while wandering, within the unit disk.
+    glance: Vector,
+    next_glance_in: f32,
+    /// Seconds since waking up, while the startled reaction runs.
+    startle: Option<f32>,
+}
+
+impl Idle {
+    pub fn new() -> Self {
+        Self {
+            rng: Rng::new(),
+            cursor_known: false,
+            idle_secs: 0.0,
+            state: IdleState::Awake,
+            glance: Vector::new(0.0, 0.0),
+            next_glance_in: 0.0,
+            startle: None,
+        }
+    }
+
+    pub fn state(&self) -> IdleState {
+        self.state
+    }
+
+    /// Advances the idle timer. `wander_after` and `sleep_after` are seconds without a cursor.
+    pub fn tick(&mut self, cursor_known: bool, dt: f32, wander_after: f32, sleep_after: f32) {
+        let dt = dt.max(0.0);
+        self.cursor_known = cursor_known;
+
+        if let Some(elapsed) = self.startle.as_mut() {
+            *elapsed += dt;
+            if *elapsed >= STARTLE_SECS {
+                self.startle = None;
+            }
+        }
+
+        if cursor_known {
+            // Startle only if the lids had started to droop.
+            if self.idle_secs > sleep_after {
+                self.startle = Some(0.0);
+            }
+            self.idle_secs = 0.0;
+            self.state = IdleState::Awake;
+            return;
+        }
+
+        self.idle_secs += dt;
+        self.state = if self.idle_secs >= sleep_after {
+            IdleState::Asleep
+        } else if self.idle_secs >= wander_after {
+            IdleState::Wandering
+        } else {
+            IdleState::Awake
+        };
+
+        if self.state == IdleState::Wandering {
+            self.next_glance_in -= dt;
+            if self.next_glance_in <= 0.0 {
+                self.next_glance_in = self.rng.range(MIN_GLANCE_SECS, MAX_GLANCE_SECS);
+                self.glance = self.next_glance();
+            }
+        } else {
+            self.glance = Vector::new(0.0, 0.0);
+            self.next_glance_in = 0.0;
+        }
+    }
+
+    /// Whether the lids are still drooping or the startled reaction is running.
+    pub fn is_animating(&self, sleep_after: f32) -> bool {
+        self.startle.is_some()
+            || (self.state == IdleState::Asleep
+                && self.idle_secs < sleep_after + FALL_ASLEEP_SECS)
+    }
+
+    /// Seconds until the state changes or the next glance, if anything is scheduled.
+    pub fn next_event_in(&self, wander_after: f32, sleep_after: f32) -> Option<f32> {
+        if self.cursor_known {
+            return None;
+        }
+        let secs = match self.state {
+            IdleState::Awake => wander_after.min(sleep_after) - self.idle_secs,
+            IdleState::Wandering => self.next_glance_in.min(sleep_after - self.idle_secs),
+            IdleState::Asleep => return None,
+        };
+        Some(secs.max(0.0))
+    }
+
+    /// Random walk over the unit disk, with an occasional look back to the middle.
+    fn next_glance(&mut self) -> Vector {
+        if self.rng.chance(RECENTER_CHANCE) {
+            return Vector::new(0.0, 0.0);
+        }
+
+        let angle = self.rng.range(0.0, std::f32::consts::TAU);
+        let step = self.rng.range(0.3, 1.0) * GLANCE_STEP;
+        let next = self.glance + Vector::new(angle.cos() * step, angle.sin() * step);
+        let len = (next.x * next.x + next.y * next.y).sqrt();
+        if len > 1.0 { next * (1.0 / len) } else { next }
+    }
+
+    /// Direction to look in (unit disk) while wandering.
+    pub fn glance(&self) -> Option<Vector> {
+        (self.state == IdleState::Wandering).then_some(self.glance)
+    }
+
+    /// Sleepy lids: the upper lid droops first, then both close.
+    pub fn lids(&self, sleep_after: f32) -> Lids {
+        if self.state != IdleState::Asleep {
+            return Lids::OPEN;
+        }
+        let t = ((self.idle_secs - sleep_after) / FALL_ASLEEP_SECS).clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        Lids {
+            upper: eased.sqrt(),
+            lower: eased * eased,
+        }
+    }
+
+    /// Whether the startled reaction after waking up is still running.
+    pub fn startled(&self) -> bool {
+        self.startle.is_some()
+    }
+
+    /// Pupil size multiplier; below `1.0` right after being startled awake.
+    pub fn pupil_scale(&self) -> f32 {
+        match self.startle {
+            Some(elapsed) => {
+                let t = (elapsed / STARTLE_SECS).clamp(0.0, 1.0);
+                STARTLE_PUPIL_SCALE + (1.0 - STARTLE_PUPIL_SCALE) * t * t
+            }
+            None => 1.0,
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::geometry::{Point, Size, Vector};
+use crate::settings::{Gaze, Settings};
+use crate::shape::Outline;
+use crate::spring::Motion;
+
+const CANVAS_INSET: f32 = 1.0;
+const SNAP_SCALE: f32 = 2.0; // snap to half-pixels
+
+/// Iris radius as a fraction of the eye's smaller half-extent.
+const IRIS_RATIO: f32 = 0.62;
+
+pub fn snap_f(value: f32) -> f32 {
+    (value * SNAP_SCALE).round() / SNAP_SCALE
+}
+
+pub fn snap_point(point: Point) -> Point {
+    Point::new(snap_f(point.x), snap_f(point.y))
+}
+
+/// How the two eyes are placed relative to each other.
+///
+/// With `Vertical` the "left" eye is the top one and the "right" eye the bottom one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrangement {
+    Horizontal,
+    Vertical,
+}
+
+impl Arrangement {
+    /// Side by side on horizontal panels and stacked on vertical ones, unless the other
+    /// arrangement gives clearly bigger eyes in a window of `size`.
+    pub fn for_panel(horizontal_panel: bool, size: Size, settings: &Settings) -> Self {
+        let (preferred, other) = if horizontal_panel {
+            (Self::Horizontal, Self::Vertical)
+        } else {
+            (Self::Vertical, Self::Horizontal)
+        };
+
+        let preferred_r = layout_and_centers(size, preferred, settings).0.eye_radius;
+        let other_r = layout_and_centers(size, other, settings).0.eye_radius;
+        if other_r > preferred_r * 1.25 { other } else { preferred }
+    }
+}
+
+/// Sizes of everything in the eyes for one window size.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    /// Half the eye's width; the height follows from the shape's aspect ratio.
+    pub eye_radius: f32,
+    pub outline: Outline,
+    pub pupil_radius: f32,
+    /// `None` when the iris is turned off.
+    pub iris_radius: Option<f32>,
+    pub eye_spacing: f32,
+    /// Virtual distance of the cursor in front of the screen when each eye is treated as a
+    /// sphere (`Gaze::Eyeball`); `None` for flat tracking.
+    pub gaze_depth: Option<f32>,
+}
+
+impl Layout {
+    /// Pupil radius at `scale` (dilation), never so big that the pupil can't move.
+    pub fn pupil_radius_at(&self, scale: f32) -> f32 {
+        let min_half = self.outline.half_width().min(self.outline.half_height());
+        (self.pupil_radius * scale).clamp(1.0, (min_half - 1.0).max(1.0))
+    }
+
+    /// How far a pupil of `pupil_radius` is turned toward the viewer's side, as `cos` of the
+    /// angle between the gaze and the screen normal (`1.0` looking straight out).
+    pub fn facing(&self, offset: Vector, pupil_radius: f32) -> f32 {
+        if self.gaze_depth.is_none() {
+            return 1.0;
+        }
+        let max = self.outline.max_offset(offset, pupil_radius);
+        if max <= 0.0 {
+            return 1.0;
+        }
+        let sin = ((offset.x * offset.x + offset.y * offset.y).sqrt() / max).min(1.0);
+        (1.0 - sin * sin).sqrt()
+    }
+}
+
+fn layout_for(size: Size, arrangement: Arrangement, settings: &Settings) -> Layout {
+    let w = size.width.max(1.0);
+    let h = size.height.max(1.0);
+
+    let aspect = settings.shape.aspect();
+    // `along` is the axis the two eyes are lined up on; the extents are per unit of radius.
+    let (along, across, along_extent, across_extent) = match arrangement {
+        Arrangement::Horizontal => (w, h, 1.0, aspect),
+        Arrangement::Vertical => (h, w, aspect, 1.0),
+    };
+
+    let min_dim = w.min(h);
+    // Round to whole logical pixels so the two eyes land on stable subpixel positions.
+    let eye_spacing = (min_dim * 0.12).clamp(2.0, 10.0).round();
+
+    // Two eyes in a row: total length is roughly `4r + spacing` for round eyes.
+    let max_r_along = ((along - eye_spacing) / (4.0 * along_extent)).max(1.0);
+    let max_r_across = (across * 0.45 / across_extent).max(1.0);
+
+    let (min_r, max_r) = settings.eye_radius_range;
+    let eye_radius = snap_f(max_r_along.min(max_r_across).clamp(min_r, max_r));
+    let outline = Outline::new(settings.shape, eye_radius, snap_f(eye_radius * aspect));
+
+    let min_half = outline.half_width().min(outline.half_height());
+    // Tiny eyes can't fit a 2 px pupil that still moves; the bounds must stay ordered.
+    let max_pupil = (min_half - 1.0).max(1.0);
+    let pupil_radius =
+        snap_f((min_half * settings.pupil_ratio).clamp(2.0_f32.min(max_pupil), max_pupil));
+
+    // Big enough to show a ring around the pupil; the sclera clips anything that sticks out.
+    let iris_radius = settings
+        .iris
+        .then(|| snap_f((min_half * IRIS_RATIO).max(pupil_radius * 1.35)));
+
+    let gaze_depth = match settings.gaze {
+        Gaze::Flat => None,
+        Gaze::Eyeball => Some(settings.cursor_depth),
+    };
+
+    Layout { eye_radius, outline, pupil_radius, iris_radius, eye_spacing, gaze_depth }
+}
+
+/// The layout for a window of `size` and the centers of the left and right eye in it.
+pub fn layout_and_centers(
+    size: Size,
+    arrangement: Arrangement,
+    settings: &Settings,
+) -> (Layout, Point, Point) {
+    let inset = CANVAS_INSET;
+    let w = (size.width - inset * 2.0).max(1.0);
+    let h = (size.height - inset * 2.0).max(1.0);
+
+    let usable_size = Size::new(w, h);
+    let layout = layout_for(usable_size, arrangement, settings);
+
+    let center = Point::new(inset + usable_size.width / 2.0, inset + usable_size.height / 2.0);
+    let step = match arrangement {
+        Arrangement::Horizontal => {
+            Vector::new(layout.outline.half_width() + layout.eye_spacing / 2.0, 0.0)
+        }
+        Arrangement::Vertical => {
+            Vector::new(0.0, layout.outline.half_height() + layout.eye_spacing / 2.0)
+        }
+    };
+    let left_eye_center = center - step;
+    let right_eye_center = center + step;
+
+    (layout, left_eye_center, right_eye_center)
+}
+
+/// Where the pupil wants to be, relative to the eye's center: toward the cursor, as far as
+/// the outline allows.
+pub fn pupil_offset(
+    eye_center: Point,
+    cursor: Point,
+    layout: &Layout,
+    pupil_radius: f32,
+) -> Vector {
+    let vx = cursor.x - eye_center.x;
+    let vy = cursor.y - eye_center.y;
+
+    let distance = (vx * vx + vy * vy).sqrt();
+    // Keep the whole pupil (at its current size) inside the outline, whatever the shape.
+    let max = layout.outline.max_offset(Vector::new(vx, vy), pupil_radius);
+
+    if let Some(depth) = layout.gaze_depth {
+        // Eyeball model: the pupil sits on a sphere and turns toward a cursor hovering `depth`
+        // in front of the screen, so its projected offset is the sine of the gaze angle. Each
+        // eye aims on its own, which makes them converge on a nearby cursor.
+        if distance <= 0.0 {
+            return Vector::new(0.0, 0.0);
+        }
+        let sin = distance / (distance * distance + depth * depth).sqrt();
+        return Vector::new(vx, vy) * (max * sin / distance);
+    }
+
+    if distance > max && distance > 0.0 {
+        let scale = max / distance;
+        Vector::new(vx * scale, vy * scale)
+    } else {
+        Vector::new(vx, vy)
+    }
+}
+
+/// Overshoot must not push the pupil through the outline: stop it at the edge.
+pub(crate) fn contain_pupil(motion: &mut Motion, layout: &Layout, pupil_radius: f32) {
+    let offset = motion.position;
+    let distance = (offset.x * offset.x + offset.y * offset.y).sqrt();
+    if distance <= 0.0 {
+        return;
+    }
+
+    let max = layout.outline.max_offset(offset, pupil_radius);
+    if distance > max {
+        let dir = Vector::new(offset.x / distance, offset.y / distance);
+        motion.position = dir * max;
+        // Drop the outward part of the velocity so it doesn't keep pushing.
+        let outward = motion.velocity.x * dir.x + motion.velocity.y * dir.y;
+        if outward > 0.0 {
+            motion.velocity = motion.velocity - dir * outward;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::EyeShape;
+
+    const SHAPES: [EyeShape; 4] =
+        [EyeShape::Circle, EyeShape::Ellipse, EyeShape::Almond, EyeShape::RoundedRect];
+
+    /// Cursor positions all around an eye at `center`, near and far.
+    fn cursors(center: Point) -> impl Iterator<Item = Point> {
+        [3.0, 40.0, 5000.0].into_iter().flat_map(move |distance| {
+            (0..72).map(move |step| {
+                let angle = step as f32 * std::f32::consts::TAU / 72.0;
+                center + Vector::new(angle.cos(), angle.sin()) * distance
+            })
+        })
+    }
+
+    #[test]
+    fn horizontal_eyes_sit_side_by_side_inside_the_window() {
+        let size = Size::new(96.0, 40.0);
+        let (layout, left, right) =
+            layout_and_centers(size, Arrangement::Horizontal, &Settings::default());
+        assert_eq!(left.y, right.y);
+        assert_eq!(left.x + right.x, size.width);
+        assert_eq!(right.x - left.x, 2.0 * layout.outline.half_width() + layout.eye_spacing);
+        assert!(left.x - layout.outline.half_width() >= CANVAS_INSET);
+        assert!(left.y - layout.outline.half_height() >= CANVAS_INSET);
+    }
+
+    #[test]
+    fn vertical_eyes_are_stacked_inside_the_window() {
+        let size = Size::new(40.0, 96.0);
+        let (layout, top, bottom) =
+            layout_and_centers(size, Arrangement::Vertical, &Settings::default());
+        assert_eq!(top.x, bottom.x);
+        assert_eq!(top.y + bottom.y, size.height);
+        assert_eq!(bottom.y - top.y, 2.0 * layout.outline.half_height() + layout.eye_spacing);
+        assert!(top.y - layout.outline.half_height() >= CANVAS_INSET);
+        assert!(top.x - layout.outline.half_width() >= CANVAS_INSET);
+    }
+
+    #[test]
+    fn panels_keep_their_arrangement_unless_the_other_is_much_bigger() {
+        let settings = Settings::default();
+        let wide = Size::new(96.0, 40.0);
+        assert_eq!(Arrangement::for_panel(true, wide, &settings), Arrangement::Horizontal);
+        // Stacked eyes would be tiny in a short, wide window.
+        assert_eq!(Arrangement::for_panel(false, wide, &settings), Arrangement::Horizontal);
+        let tall = Size::new(40.0, 96.0);
+        assert_eq!(Arrangement::for_panel(false, tall, &settings), Arrangement::Vertical);
+    }
+
+    #[test]
+    fn pupils_stay_inside_every_shape() {
+        for shape in SHAPES {
+            for gaze in [Gaze::Flat, Gaze::Eyeball] {
+                let settings = Settings { shape, gaze, pupil_ratio: 0.6, ..Settings::default() };
+                let (layout, center, _) =
+                    layout_and_centers(Size::new(128.0, 48.0), Arrangement::Horizontal, &settings);
+                for scale in [0.5, 1.0, 1.6] {
+                    let radius = layout.pupil_radius_at(scale);
+                    for cursor in cursors(center) {
+                        let offset = pupil_offset(center, cursor, &layout, radius);
+                        let depth = layout.outline.depth(offset);
+                        assert!(
+                            depth >= radius - 1e-3,
+                            "{shape:?} {gaze:?}: pupil {radius} at {offset:?} is {depth} deep"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_smallest_eyes_still_get_a_pupil() {
+        // The smallest `min_eye_radius` the applet's configuration accepts; tiny windows fall
+        // back to it.
+        let min_radius = 2.0;
+        for shape in SHAPES {
+            for pupil_ratio in [0.1, 0.5, 0.8] {
+                let settings = Settings {
+                    shape,
+                    pupil_ratio,
+                    eye_radius_range: (min_radius, min_radius),
+                    ..Settings::default()
+                };
+                for size in [Size::new(1.0, 1.0), Size::new(96.0, 40.0)] {
+                    let (layout, _, _) =
+                        layout_and_centers(size, Arrangement::Horizontal, &settings);
+                    let min_half = layout.outline.half_width().min(layout.outline.half_height());
+                    let radius = layout.pupil_radius;
+                    assert!(radius > 0.0, "{shape:?} at ratio {pupil_ratio}: pupil {radius}");
+                    assert!(
+                        radius <= (min_half - 1.0).max(1.0),
+                        "{shape:?} at ratio {pupil_ratio}: pupil {radius} in {min_half}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flat_pupils_reach_the_cursor_inside_the_eye() {
+        let size = Size::new(96.0, 40.0);
+        let (layout, center, _) =
+            layout_and_centers(size, Arrangement::Horizontal, &Settings::default());
+        let offset = Vector::new(1.0, -1.0);
+        assert_eq!(pupil_offset(center, center + offset, &layout, layout.pupil_radius), offset);
+    }
+}
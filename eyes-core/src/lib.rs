@@ -0,0 +1,35 @@
+//! The eyes without a GUI: layout, pupil tracking, blinks, idle behavior and expressions, plus
+//! the calibration of global cursor positions.
+//!
+//! Everything here works in logical pixels with its own small geometry types; frontends
+//! convert at the edges, feed the cursor to [`Eyes::tick`] and draw the [`Scene`] built
+//! from the accessors.
+
+mod blink;
+mod calibration;
+mod dilation;
+mod expression;
+mod eyes;
+mod geometry;
+mod idle;
+mod layout;
+mod lids;
+mod palette;
+mod rng;
+mod scene;
+mod settings;
+mod shape;
+mod spring;
+
+pub use blink::Lids;
+pub use calibration::{Calibrator, Estimate};
+pub use expression::{Expression, Pose};
+pub use eyes::Eyes;
+pub use geometry::{Point, Size, Vector};
+pub use idle::IdleState;
+pub use layout::{layout_and_centers, pupil_offset, snap_f, snap_point, Arrangement, Layout};
+pub use lids::{brow_points, LidEdge};
+pub use palette::{Color, Palette};
+pub use scene::{Item, Paint, Scene, Shape, Stroke, Style};
+pub use settings::{Gaze, Settings};
+pub use shape::{ellipse_points, EyeShape, Outline};
@@ -0,0 +1,93 @@
+use crate::expression::Pose;
+use crate::geometry::Vector;
+use crate::shape::Outline;
+
+// Fraction of the eye's height each lid covers when fully closed; they meet just below center.
+const UPPER_LID_TRAVEL: f32 = 0.62;
+const LOWER_LID_TRAVEL: f32 = 1.0 - UPPER_LID_TRAVEL;
+
+const BROW_SEGMENTS: usize = 8;
+
+/// Where a lid's edge sits, relative to the eye's center (y-down).
+#[derive(Debug, Clone, Copy)]
+pub struct LidEdge {
+    dy: f32,
+    left: f32,
+    right: f32,
+    /// How far the middle of the edge bulges toward the eye's center.
+    bow: f32,
+    /// How far the right end of the edge sits below the middle (the left end mirrors it).
+    tilt: f32,
+}
+
+impl LidEdge {
+    const SEGMENTS: usize = 8;
+
+    /// `bow` scales the resting curvature; `slant` tilts the edge down toward the right end,
+    /// in half eye heights.
+    pub fn new(outline: &Outline, closure: f32, upper: bool, bow: f32, slant: f32) -> Option<Self> {
+        let closure = closure.clamp(0.0, 1.0);
+        let half_height = outline.half_height();
+        let travel = if upper { UPPER_LID_TRAVEL } else { LOWER_LID_TRAVEL };
+        let depth = 2.0 * half_height * travel * closure;
+        if depth < 0.25 {
+            return None;
+        }
+
+        let dy = if upper { -half_height + depth } else { half_height - depth };
+        let (left, right) = outline.span_at(dy)?;
+        // The lids bulge toward each other and flatten out (and level) as they meet.
+        let bow = (right - left) / 2.0 * 0.3 * bow * (1.0 - closure);
+        Some(Self {
+            dy,
+            left,
+            right,
+            bow: if upper { bow } else { -bow },
+            tilt: half_height * slant * (1.0 - closure),
+        })
+    }
+
+    /// The edge as a polyline from left to right, relative to the eye's center.
+    pub fn points(&self) -> Vec<Vector> {
+        let mid = (self.left + self.right) / 2.0;
+        let half = ((self.right - self.left) / 2.0).max(f32::EPSILON);
+        let mut points: Vec<Vector> = (0..=Self::SEGMENTS)
+            .map(|i| {
+                let x = self.left + (self.right - self.left) * i as f32 / Self::SEGMENTS as f32;
+                let u = (x - mid) / half;
+                Vector::new(x, self.dy + self.bow * (1.0 - u * u) + self.tilt * u)
+            })
+            .collect();
+
+        // A tilted edge can end inside the outline: carry the end segments on along their own
+        // lines (clipping trims them back to the outline).
+        let reach = Self::SEGMENTS as f32 / 2.0;
+        let last = points.len() - 1;
+        points[0] = points[0] + (points[0] - points[1]) * reach;
+        points[last] = points[last] + (points[last] - points[last - 1]) * reach;
+        points
+    }
+}
+
+/// A brow `width` thick as a polyline relative to the eye's center, with `room` pixels of
+/// free space above the eye. Without enough room it is pushed down over the top of the eye.
+/// `inner` is `1.0` when the other eye is to the right and `-1.0` when it is to the left.
+pub fn brow_points(
+    outline: &Outline,
+    pose: &Pose,
+    inner: f32,
+    room: f32,
+    width: f32,
+) -> Vec<Vector> {
+    let unit = outline.half_height();
+    let rest = -unit - width;
+    let highest = -unit - room + width / 2.0 + 0.5;
+    (0..=BROW_SEGMENTS)
+        .map(|i| {
+            let u = 2.0 * i as f32 / BROW_SEGMENTS as f32 - 1.0;
+            let lift = pose.brow_lift + pose.brow_arch * (1.0 - u * u);
+            let y = rest - unit * lift + unit * pose.brow_slant * u * inner;
+            Vector::new(outline.half_width() * 0.8 * u, y.max(highest))
+        })
+        .collect()
+}
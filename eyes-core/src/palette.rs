@@ -0,0 +1,92 @@
+/// A color with straight (not premultiplied) alpha, every channel in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0)
+    }
+}
+
+/// All colors used to draw the eyes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub sclera: Color,
+    /// Sclera tint while the cursor position is unknown.
+    pub sclera_unknown: Color,
+    pub outline: Color,
+    pub shadow: Color,
+    pub highlight: Color,
+    pub pupil: Color,
+    pub pupil_highlight: Color,
+    pub lid: Color,
+    pub iris: Color,
+    /// Darker ring at the iris' outer edge.
+    pub limbal: Color,
+    pub striation: Color,
+}
+
+impl Palette {
+    /// Colors for a dark or light theme with the given `accent` color; `iris` overrides the
+    /// accent-derived iris color.
+    pub fn new(dark: bool, accent: Color, iris: Option<[u8; 3]>) -> Self {
+        let iris = iris.map_or(accent, |[r, g, b]| Color::from_rgb8(r, g, b));
+        let limbal = mix(iris, Color::BLACK, 0.55);
+        let striation = Color { a: 0.45, ..mix(iris, Color::BLACK, 0.35) };
+
+        if dark {
+            let sclera = Color::from_rgb8(232, 232, 232);
+            Self {
+                sclera,
+                sclera_unknown: mix(sclera, accent, 0.40),
+                outline: Color::from_rgb8(8, 8, 8),
+                shadow: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.18 },
+                highlight: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.16 },
+                pupil: mix(Color::from_rgb8(10, 10, 10), accent, 0.12),
+                pupil_highlight: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.26 },
+                lid: mix(accent, Color::BLACK, 0.45),
+                iris,
+                limbal,
+                striation,
+            }
+        } else {
+            let sclera = Color::from_rgb8(250, 250, 250);
+            Self {
+                sclera,
+                sclera_unknown: mix(sclera, accent, 0.35),
+                outline: Color::from_rgb8(24, 24, 24),
+                shadow: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.12 },
+                highlight: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.22 },
+                pupil: mix(Color::from_rgb8(12, 12, 12), accent, 0.10),
+                pupil_highlight: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.30 },
+                lid: mix(accent, Color::WHITE, 0.55),
+                iris,
+                limbal,
+                striation,
+            }
+        }
+    }
+}
+
+/// Linear blend from `a` (`t = 0`) to `b` (`t = 1`), keeping `a`'s alpha.
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    Color {
+        r: a.r + (b.r - a.r) * t,
+        g: a.g + (b.g - a.g) * t,
+        b: a.b + (b.b - a.b) * t,
+        a: a.a,
+    }
+}
@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+
+use crate::blink::Lids;
+use crate::eyes::Eyes;
+use crate::geometry::{Point, Size, Vector};
+use crate::layout::{layout_and_centers, snap_point, Arrangement, Layout};
+use crate::lids::{brow_points, LidEdge};
+use crate::palette::{Color, Palette};
+use crate::shape::{ellipse_points, Outline};
+
+// How much of the iris is the darker limbal ring.
+const LIMBAL_RING: f32 = 0.18;
+const STRIATIONS: usize = 18;
+
+/// Visual style of the eyes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Style {
+    /// Shaded sclera with glossy highlights.
+    #[default]
+    Classic,
+    /// Plain fills and outline only.
+    Flat,
+}
+
+/// One thing to draw, in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub shape: Shape,
+    pub paint: Paint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Closed polygon.
+    Polygon(Vec<Point>),
+    /// Open path through the points.
+    Polyline(Vec<Point>),
+    /// Separate line segments.
+    Segments(Vec<(Point, Point)>),
+    Circle { center: Point, radius: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Paint {
+    /// Nonzero fill.
+    Fill(Color),
+    Stroke(Stroke),
+}
+
+/// A stroke centered on the shape; joins are mitered and caps butt unless rounded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub color: Color,
+    pub width: f32,
+    pub round_join: bool,
+    pub round_cap: bool,
+}
+
+/// One frame of the eyes as backend-independent items, in four layers from bottom to top:
+/// background (sclera and shading), moving parts (iris, pupils, lids), outline and brows.
+///
+/// The background and outline only change with the palette, style, settings, arrangement,
+/// size and whether the cursor is known, so frontends can cache them.
+#[derive(Debug)]
+pub struct Scene<'a> {
+    eyes: &'a Eyes,
+    palette: Palette,
+    style: Style,
+    striations: bool,
+    size: Size,
+    layout: Layout,
+    centers: [Point; 2],
+}
+
+impl<'a> Scene<'a> {
+    pub fn new(
+        eyes: &'a Eyes,
+        size: Size,
+        palette: Palette,
+        style: Style,
+        striations: bool,
+    ) -> Self {
+        let (layout, left_eye_center, right_eye_center) =
+            layout_and_centers(size, eyes.arrangement(), eyes.settings());
+        Self {
+            eyes,
+            palette,
+            style,
+            striations,
+            size,
+            layout,
+            centers: [snap_point(left_eye_center), snap_point(right_eye_center)],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Centers of the left (or top) and right eye, snapped like everything drawn around them.
+    pub fn centers(&self) -> [Point; 2] {
+        self.centers
+    }
+
+    /// All layers in drawing order.
+    pub fn items(&self) -> Vec<Item> {
+        let mut items = self.background();
+        items.extend(self.moving_parts());
+        items.extend(self.outline());
+        items.extend(self.brows());
+        items
+    }
+
+    /// Sclera and (for `Style::Classic`) its shading.
+    pub fn background(&self) -> Vec<Item> {
+        let palette = &self.palette;
+        let sclera =
+            if self.eyes.cursor_known() { palette.sclera } else { palette.sclera_unknown };
+        let outline = &self.layout.outline;
+        let rx = outline.half_width();
+        let ry = outline.half_height();
+        let min_half = rx.min(ry);
+
+        let mut items = Vec::new();
+        for center in self.centers {
+            items.push(fill(polygon(center, outline.points()), sclera));
+            if self.style != Style::Classic {
+                continue;
+            }
+
+            // These highlights must stay inside the sclera (there is no path-clip),
+            // otherwise semi-transparent pixels "bleed" outside the outline.
+            let shadow_center =
+                snap_point(Point::new(center.x + rx * 0.10, center.y + ry * 0.12));
+            let shadow_radius =
+                outline.clamp_radius(shadow_center - center, min_half * 0.78, 1.0);
+            if shadow_radius > 0.0 {
+                items.push(fill(circle(shadow_center, shadow_radius), palette.shadow));
+            }
+
+            let highlight_center =
+                snap_point(Point::new(center.x - rx * 0.18, center.y - ry * 0.22));
+            let highlight_radius =
+                outline.clamp_radius(highlight_center - center, min_half * 0.55, 1.0);
+            if highlight_radius > 0.0 {
+                items.push(fill(circle(highlight_center, highlight_radius), palette.highlight));
+            }
+        }
+        items
+    }
+
+    /// Iris, pupil and lids of both eyes.
+    pub fn moving_parts(&self) -> Vec<Item> {
+        let [left_eye_center, right_eye_center] = self.centers;
+        let mut items = Vec::new();
+        for (center, pupil, lids, inner) in [
+            (left_eye_center, self.eyes.left_pupil(), self.eyes.left_lids(), 1.0),
+            (right_eye_center, self.eyes.right_pupil(), self.eyes.right_lids(), -1.0),
+        ] {
+            self.eye_moving_parts(&mut items, center, pupil, lids, inner);
+        }
+        items
+    }
+
+    /// Outlines of both eyes, over the lids so they cover their outer edge.
+    pub fn outline(&self) -> Vec<Item> {
+        let stroke_outline = self.layout.outline.inset(0.5);
+        self.centers
+            .iter()
+            .map(|&center| {
+                stroke(polygon(center, stroke_outline.points()), self.outline_stroke())
+            })
+            .collect()
+    }
+
+    /// Brows over both eyes; empty for expressions without them.
+    pub fn brows(&self) -> Vec<Item> {
+        let pose = self.eyes.pose();
+        if pose.brow <= 0.01 {
+            return Vec::new();
+        }
+        let outline = &self.layout.outline;
+        let brow_stroke = Stroke {
+            color: Color {
+                a: self.palette.outline.a * pose.brow.clamp(0.0, 1.0),
+                ..self.palette.outline
+            },
+            width: (outline.half_height() * 0.16).max(1.5),
+            round_join: false,
+            round_cap: true,
+        };
+        let [left_eye_center, right_eye_center] = self.centers;
+        [(left_eye_center, 1.0), (right_eye_center, -1.0)]
+            .into_iter()
+            .map(|(center, inner)| {
+                // Stacked eyes: the lower brow only has the gap between the eyes.
+                let room = match self.eyes.arrangement() {
+                    Arrangement::Vertical if inner < 0.0 => self.layout.eye_spacing,
+                    _ => center.y - outline.half_height(),
+                };
+                let points = brow_points(outline, &pose, inner, room, brow_stroke.width);
+                stroke(Shape::Polyline(offset(center, &points)), brow_stroke)
+            })
+            .collect()
+    }
+
+    /// The outline is stroked just inside the eye; round joins keep pointy corners inside.
+    fn outline_stroke(&self) -> Stroke {
+        Stroke { color: self.palette.outline, width: 1.0, round_join: true, round_cap: false }
+    }
+
+    /// Iris, pupil and lids of one eye; `inner` is `1.0` for the left (or top) eye and `-1.0`
+    /// for the other one.
+    fn eye_moving_parts(
+        &self,
+        items: &mut Vec<Item>,
+        center: Point,
+        pupil_offset: Vector,
+        lids: Lids,
+        inner: f32,
+    ) {
+        let layout = &self.layout;
+        let palette = &self.palette;
+        let pupil_center = center + pupil_offset;
+        let pose = self.eyes.pose();
+
+        // Same size `tick` contained the pupil with.
+        let pupil_radius = layout.pupil_radius_at(self.eyes.pupil_scale());
+        // A pupil turned sideways is foreshortened along the gaze direction.
+        let facing = layout.facing(pupil_offset, pupil_radius);
+
+        if let Some(iris_radius) = layout.iris_radius {
+            self.iris(items, center, pupil_offset, iris_radius, pupil_radius, facing);
+        }
+
+        let pupil = if facing < 0.99 {
+            let points =
+                ellipse_points(pupil_offset, pupil_radius * facing.max(0.35), pupil_radius);
+            polygon(pupil_center, &points)
+        } else {
+            circle(pupil_center, pupil_radius)
+        };
+        items.push(fill(pupil, palette.pupil));
+        if self.style == Style::Classic {
+            let glint = Point::new(
+                pupil_center.x - pupil_radius * 0.35,
+                pupil_center.y - pupil_radius * 0.35,
+            );
+            items.push(fill(
+                circle(glint, (pupil_radius * 0.28).max(1.0)),
+                palette.pupil_highlight,
+            ));
+        }
+
+        for (closure, upper, bow, slant) in [
+            (lids.upper, true, pose.upper_bow, pose.lid_slant * inner),
+            (lids.lower, false, pose.lower_bow, 0.0),
+        ] {
+            let Some(edge) = LidEdge::new(&layout.outline, closure, upper, bow, slant) else {
+                continue;
+            };
+            if let Some(lid) = lid_shape(center, &layout.outline, &edge, upper) {
+                items.push(fill(lid, palette.lid));
+            }
+            let edge_line = lid_edge_shape(center, &layout.outline, &edge);
+            items.push(stroke(edge_line, self.outline_stroke()));
+        }
+    }
+
+    /// Iris around the pupil at `pupil_offset`, foreshortened like the pupil.
+    ///
+    /// The iris can be larger than the room the pupil has, so it is clipped to the sclera (one
+    /// pixel in, under the outline stroke).
+    fn iris(
+        &self,
+        items: &mut Vec<Item>,
+        center: Point,
+        pupil_offset: Vector,
+        iris_radius: f32,
+        pupil_radius: f32,
+        facing: f32,
+    ) {
+        let outline = &self.layout.outline;
+        let squash = facing.max(0.35);
+        let ring = |radius: f32| -> Vec<Vector> {
+            let points: Vec<Vector> = ellipse_points(pupil_offset, radius * squash, radius)
+                .into_iter()
+                .map(|point| point + pupil_offset)
+                .collect();
+            outline.clip_polygon(&points, 1.0)
+        };
+
+        let outer = ring(iris_radius);
+        if outer.len() >= 3 {
+            items.push(fill(polygon(center, &outer), self.palette.limbal));
+        }
+        let inner = ring(iris_radius * (1.0 - LIMBAL_RING));
+        if inner.len() >= 3 {
+            items.push(fill(polygon(center, &inner), self.palette.iris));
+        }
+
+        // Too fine to read on small eyes.
+        if !self.striations || iris_radius < 6.0 {
+            return;
+        }
+        let from = pupil_radius * 1.1;
+        let to = iris_radius * (1.0 - LIMBAL_RING);
+        let distance = pupil_offset.length();
+        let axis = if distance > 0.0 {
+            pupil_offset * (1.0 / distance)
+        } else {
+            Vector::new(1.0, 0.0)
+        };
+        let normal = Vector::new(-axis.y, axis.x);
+        let lines = (0..STRIATIONS)
+            .filter_map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / STRIATIONS as f32;
+                let dir = axis * (angle.cos() * squash) + normal * angle.sin();
+                outline.clip_segment(pupil_offset + dir * from, pupil_offset + dir * to, 1.0)
+            })
+            .map(|(a, b)| (center + a, center + b))
+            .collect();
+        let striation = Stroke {
+            color: self.palette.striation,
+            width: 0.75,
+            round_join: false,
+            round_cap: false,
+        };
+        items.push(stroke(Shape::Segments(lines), striation));
+    }
+}
+
+fn fill(shape: Shape, color: Color) -> Item {
+    Item { shape, paint: Paint::Fill(color) }
+}
+
+fn stroke(shape: Shape, stroke: Stroke) -> Item {
+    Item { shape, paint: Paint::Stroke(stroke) }
+}
+
+fn circle(center: Point, radius: f32) -> Shape {
+    Shape::Circle { center, radius }
+}
+
+fn offset(center: Point, points: &[Vector]) -> Vec<Point> {
+    points.iter().map(|point| center + *point).collect()
+}
+
+/// Closed polygon through `points` (relative to `center`).
+fn polygon(center: Point, points: &[Vector]) -> Shape {
+    Shape::Polygon(offset(center, points))
+}
+
+/// The part of the eye above (`upper`) or below the lid edge.
+///
+/// Its outer boundary is the eye outline itself, so the outline stroke drawn on top hides it
+/// and no seam shows between lid and outline.
+fn lid_shape(center: Point, outline: &Outline, edge: &LidEdge, upper: bool) -> Option<Shape> {
+    let polygon_points = outline.clip(&edge.points(), upper);
+    (polygon_points.len() >= 3).then(|| polygon(center, &polygon_points))
+}
+
+/// The lid edge, kept one pixel inside the outline so its stroke can't poke out.
+fn lid_edge_shape(center: Point, outline: &Outline, edge: &LidEdge) -> Shape {
+    Shape::Segments(
+        edge.points()
+            .windows(2)
+            .filter_map(|pair| outline.clip_segment(pair[0], pair[1], 1.0))
+            .map(|(a, b)| (center + a, center + b))
+            .collect(),
+    )
+}
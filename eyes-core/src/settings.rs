@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::shape::EyeShape;
+
+/// How the pupils aim at the cursor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gaze {
+    /// Pupils move straight toward the cursor (classic xeyes).
+    #[default]
+    Flat,
+    /// Each eye is a sphere looking at the cursor at `cursor_depth` in front of the screen;
+    /// the eyes converge on a nearby cursor and pupils foreshorten when looking sideways.
+    Eyeball,
+}
+
+/// What the eye model needs from the user's settings.
+///
+/// Frontends fill it from their own configuration and are expected to clamp the values to
+/// sane ranges first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// `(min, max)` eye radius in logical pixels.
+    pub eye_radius_range: (f32, f32),
+    /// Pupil radius as a fraction of the eye radius.
+    pub pupil_ratio: f32,
+    pub shape: EyeShape,
+    /// Leave room for an iris around the pupil.
+    pub iris: bool,
+    pub gaze: Gaze,
+    /// Virtual distance of the cursor in front of the screen for `Gaze::Eyeball`.
+    pub cursor_depth: f32,
+    /// Natural frequency (rad/s) of the pupil spring.
+    pub follow_speed: f32,
+    /// Damping ratio of the pupil spring.
+    pub follow_damping: f32,
+    /// Pupils react to cursor distance and speed.
+    pub pupil_dilation: bool,
+    /// Pupils are a little wider on a dark theme.
+    pub pupil_theme_response: bool,
+    /// Seconds without a known cursor before the eyes start looking around on their own.
+    pub wander_after_secs: f32,
+    /// Seconds without a known cursor before the eyes fall asleep.
+    pub sleep_after_secs: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            eye_radius_range: (6.0, 32.0),
+            pupil_ratio: 0.38,
+            shape: EyeShape::Circle,
+            iris: true,
+            gaze: Gaze::Flat,
+            cursor_depth: 150.0,
+            follow_speed: 12.0,
+            follow_damping: 0.8,
+            pupil_dilation: true,
+            pupil_theme_response: false,
+            wander_after_secs: 8.0,
+            sleep_after_secs: 90.0,
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::geometry::Vector;
+
+/// Outline of a single eye.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EyeShape {
+    #[default]
+    Circle,
+    /// Tall ellipse, like classic xeyes.
+    Ellipse,
+    /// Wide, pointed at both corners.
+    Almond,
+    RoundedRect,
+}
+
+impl EyeShape {
+    /// Height / width.
+    pub fn aspect(self) -> f32 {
+        match self {
+            Self::Circle | Self::RoundedRect => 1.0,
+            Self::Ellipse => 1.35,
+            Self::Almond => 0.62,
+        }
+    }
+}
+
+/// A convex polygon approximating an `EyeShape`, relative to the eye's center (y-down).
+///
+/// Drawing and all containment math use the same polygon, so anything clamped against it
+/// stays inside what is actually drawn.
+#[derive(Debug, Clone)]
+pub struct Outline {
+    shape: EyeShape,
+    points: Vec<Vector>,
+    /// Outward unit normal and offset per edge: a point `p` is inside when `n·p <= d` for all.
+    edges: Vec<(Vector, f32)>,
+    half_width: f32,
+    half_height: f32,
+}
+
+impl Outline {
+    pub fn new(shape: EyeShape, half_width: f32, half_height: f32) -> Self {
+        let rx = half_width.max(0.5);
+        let ry = half_height.max(0.5);
+        // Keep segments around 2px so large eyes stay smooth.
+        let steps = (PI * (rx + ry) / 2.0).ceil().clamp(32.0, 256.0) as usize;
+
+        let points = match shape {
+            EyeShape::Circle | EyeShape::Ellipse => (0..steps)
+                .map(|i| {
+                    let angle = TAU * i as f32 / steps as f32;
+                    Vector::new(rx * angle.cos(), ry * angle.sin())
+                })
+                .collect(),
+            EyeShape::Almond => almond_points(rx, ry, steps),
+            EyeShape::RoundedRect => rounded_rect_points(rx, ry, 0.35 * rx.min(ry), steps),
+        };
+
+        Self::from_points(shape, points, rx, ry)
+    }
+
+    fn from_points(
+        shape: EyeShape,
+        points: Vec<Vector>,
+        half_width: f32,
+        half_height: f32,
+    ) -> Self {
+        let edges = (0..points.len())
+            .map(|i| {
+                let a = points[i];
+                let b = points[(i + 1) % points.len()];
+                let mut normal = normalize(Vector::new(b.y - a.y, a.x - b.x));
+                // Points wind either way depending on the shape; make the normal point out.
+                if dot(normal, a) < 0.0 {
+                    normal = Vector::new(-normal.x, -normal.y);
+                }
+                (normal, dot(normal, a))
+            })
+            .collect();
+
+        Self { shape, points, edges, half_width, half_height }
+    }
+
+    /// The same polygon with every edge moved `by` inward (half extents shrink by `by` too), so
+    /// a stroke of width `2 * by` along it stays inside the eye, pointed corners included.
+    /// Shrinking the radii instead would leave the almond's corners where they were, and the
+    /// stroke would poke out there. Only corners sharper than about 25° move less than `by`.
+    pub fn inset(&self, by: f32) -> Self {
+        let count = self.points.len();
+        let points = (0..count)
+            .map(|i| {
+                let (before, _) = self.edges[(i + count - 1) % count];
+                let (after, _) = self.edges[i];
+                // Lands `by` inside both edges meeting at this corner.
+                let scale = by / (1.0 + dot(before, after)).max(0.1);
+                self.points[i] - (before + after) * scale
+            })
+            .collect();
+        Self::from_points(self.shape, points, self.half_width - by, self.half_height - by)
+    }
+
+    pub fn half_width(&self) -> f32 {
+        self.half_width
+    }
+
+    pub fn half_height(&self) -> f32 {
+        self.half_height
+    }
+
+    /// Corners of the polygon, relative to the eye's center.
+    pub fn points(&self) -> &[Vector] {
+        &self.points
+    }
+
+    /// Distance from `offset` to the outline, positive inside and negative outside.
+    pub fn depth(&self, offset: Vector) -> f32 {
+        self.edges
+            .iter()
+            .map(|(normal, d)| d - dot(*normal, offset))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Largest radius up to `desired` for a circle at `offset` that stays `inset` inside.
+    pub fn clamp_radius(&self, offset: Vector, desired: f32, inset: f32) -> f32 {
+        desired.min(self.depth(offset) - inset).max(0.0)
+    }
+
+    /// How far a circle of radius `clearance` can move from the center along `direction`
+    /// before it touches the outline.
+    pub fn max_offset(&self, direction: Vector, clearance: f32) -> f32 {
+        let direction = normalize(direction);
+        self.edges
+            .iter()
+            .filter_map(|(normal, d)| {
+                let along = dot(*normal, direction);
+                (along > 1e-6).then(|| (d - clearance) / along)
+            })
+            .fold(f32::INFINITY, f32::min)
+            .max(0.0)
+    }
+
+    /// Horizontal extent `(left, right)` of the outline at height `dy`, if it reaches there.
+    pub fn span_at(&self, dy: f32) -> Option<(f32, f32)> {
+        let mut span: Option<(f32, f32)> = None;
+        for i in 0..self.points.len() {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % self.points.len()];
+            if (a.y - dy) * (b.y - dy) > 0.0 || a.y == b.y {
+                continue;
+            }
+            let x = a.x + (b.x - a.x) * (dy - a.y) / (b.y - a.y);
+            span = Some(match span {
+                Some((left, right)) => (left.min(x), right.max(x)),
+                None => (x, x),
+            });
+        }
+        span.filter(|(left, right)| right > left)
+    }
+
+    /// The part of the outline on the kept side of every line through consecutive `cut`
+    /// points (kept side: left of travel when `keep_left`, in y-down coordinates).
+    pub fn clip(&self, cut: &[Vector], keep_left: bool) -> Vec<Vector> {
+        let mut polygon = self.points.clone();
+        for pair in cut.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let side = |p: Vector| {
+                let cross = (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+                if keep_left { cross } else { -cross }
+            };
+            polygon = clip_half_plane(&polygon, side);
+            if polygon.is_empty() {
+                break;
+            }
+        }
+        polygon
+    }
+
+    /// The part of a convex `polygon` lying at least `inset` inside the outline.
+    pub fn clip_polygon(&self, polygon: &[Vector], inset: f32) -> Vec<Vector> {
+        let mut polygon = polygon.to_vec();
+        for (normal, d) in &self.edges {
+            polygon = clip_half_plane(&polygon, |p| dot(*normal, p) - (d - inset));
+            if polygon.is_empty() {
+                break;
+            }
+        }
+        polygon
+    }
+
+    /// The part of segment `a`–`b` lying at least `inset` inside the outline.
+    pub fn clip_segment(&self, a: Vector, b: Vector, inset: f32) -> Option<(Vector, Vector)> {
+        let delta = b - a;
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        for (normal, d) in &self.edges {
+            let limit = d - inset - dot(*normal, a);
+            let rate = dot(*normal, delta);
+            if rate.abs() < 1e-6 {
+                if limit < 0.0 {
+                    return None;
+                }
+            } else if rate > 0.0 {
+                t1 = t1.min(limit / rate);
+            } else {
+                t0 = t0.max(limit / rate);
+            }
+            if t0 > t1 {
+                return None;
+            }
+        }
+        Some((a + delta * t0, a + delta * t1))
+    }
+}
+
+/// Points of an ellipse around the origin, with radius `along` in the direction of `axis` and
+/// `across` perpendicular to it.
+pub fn ellipse_points(axis: Vector, along: f32, across: f32) -> Vec<Vector> {
+    let axis = normalize(axis);
+    // Without a direction the orientation doesn't matter as long as it is valid.
+    let axis = if axis == Vector::new(0.0, 0.0) { Vector::new(1.0, 0.0) } else { axis };
+    let normal = Vector::new(-axis.y, axis.x);
+    let steps = (PI * (along + across) / 2.0).ceil().clamp(16.0, 128.0) as usize;
+    (0..steps)
+        .map(|i| {
+            let angle = TAU * i as f32 / steps as f32;
+            axis * (along * angle.cos()) + normal * (across * angle.sin())
+        })
+        .collect()
+}
+
+fn almond_points(rx: f32, ry: f32, steps: usize) -> Vec<Vector> {
+    // Two circular arcs meeting at the corners `(±rx, 0)`; each arc's center sits `c` past
+    // the eye's center on the opposite side. Only works for wide almonds.
+    let ry = ry.min(rx);
+    let c = (rx * rx - ry * ry) / (2.0 * ry);
+    let radius = ry + c;
+    let half = steps / 2;
+
+    let mut points = Vec::with_capacity(half * 2);
+    // Top arc left to right, then bottom arc right to left.
+    for (sign, from) in [(-1.0f32, PI), (1.0f32, 0.0)] {
+        for i in 0..half {
+            // Cosine spacing keeps points dense near the corners, where the arcs are steep.
+            let x = rx * (from + PI * i as f32 / half as f32).cos();
+            let y = sign * ((radius * radius - x * x).max(0.0).sqrt() - c);
+            points.push(Vector::new(x, y));
+        }
+    }
+    points
+}
+
+fn rounded_rect_points(rx: f32, ry: f32, corner: f32, steps: usize) -> Vec<Vector> {
+    let per_corner = (steps / 4).max(2);
+    let mut points = Vec::with_capacity(per_corner * 4);
+    for (cx, cy, start) in [
+        (rx - corner, ry - corner, 0.0),
+        (-(rx - corner), ry - corner, FRAC_PI_2),
+        (-(rx - corner), -(ry - corner), PI),
+        (rx - corner, -(ry - corner), PI + FRAC_PI_2),
+    ] {
+        for i in 0..per_corner {
+            let angle = start + FRAC_PI_2 * i as f32 / (per_corner - 1) as f32;
+            points.push(Vector::new(cx + corner * angle.cos(), cy + corner * angle.sin()));
+        }
+    }
+    points
+}
+
+/// Sutherland–Hodgman against a single half-plane `side(p) <= 0`.
+fn clip_half_plane(polygon: &[Vector], side: impl Fn(Vector) -> f32) -> Vec<Vector> {
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let (sa, sb) = (side(a), side(b));
+        if sa <= 0.0 {
+            out.push(a);
+        }
+        if (sa <= 0.0) != (sb <= 0.0) {
+            out.push(a + (b - a) * (sa / (sa - sb)));
+        }
+    }
+    out
+}
+
+fn dot(a: Vector, b: Vector) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+fn normalize(v: Vector) -> Vector {
+    let len = (v.x * v.x + v.y * v.y).sqrt();
+    if len > 0.0 { Vector::new(v.x / len, v.y / len) } else { Vector::new(0.0, 0.0) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHAPES: [EyeShape; 4] =
+        [EyeShape::Circle, EyeShape::Ellipse, EyeShape::Almond, EyeShape::RoundedRect];
+
+    fn outline(shape: EyeShape) -> Outline {
+        Outline::new(shape, 20.0, 20.0 * shape.aspect())
+    }
+
+    fn directions() -> impl Iterator<Item = Vector> {
+        (0..72).map(|step| {
+            let angle = step as f32 * TAU / 72.0;
+            Vector::new(angle.cos(), angle.sin())
+        })
+    }
+
+    #[test]
+    fn max_offset_touches_the_outline() {
+        for shape in SHAPES {
+            let outline = outline(shape);
+            for direction in directions() {
+                for clearance in [0.0, 3.0, 6.0] {
+                    let offset = direction * outline.max_offset(direction, clearance);
+                    let depth = outline.depth(offset);
+                    assert!((depth - clearance).abs() < 1e-3, "{shape:?} {direction:?}: {depth}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clamped_circles_stay_inside() {
+        for shape in SHAPES {
+            let outline = outline(shape);
+            for direction in directions() {
+                for distance in [0.0, 5.0, 15.0, 30.0] {
+                    let offset = direction * distance;
+                    let radius = outline.clamp_radius(offset, 8.0, 1.0);
+                    assert!((0.0..=8.0).contains(&radius));
+                    if radius > 0.0 {
+                        assert!(outline.depth(offset) - radius >= 1.0 - 1e-4, "{shape:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clip_keeps_one_side_of_the_cut() {
+        let outline = outline(EyeShape::Circle);
+        // Left to right through the center: the left of travel is up (y-down).
+        let cut = [Vector::new(-30.0, 0.0), Vector::new(30.0, 0.0)];
+        let upper = outline.clip(&cut, true);
+        assert!(!upper.is_empty());
+        assert!(upper.iter().all(|point| point.y <= 1e-4));
+        assert!(upper.iter().any(|point| point.y < -19.0));
+        let lower = outline.clip(&cut, false);
+        assert!(lower.iter().all(|point| point.y >= -1e-4));
+
+        // A cut missing the eye keeps all of it on one side and nothing on the other.
+        let above = [Vector::new(-30.0, -25.0), Vector::new(30.0, -25.0)];
+        assert!(outline.clip(&above, true).is_empty());
+        assert_eq!(outline.clip(&above, false).len(), outline.points().len());
+    }
+
+    #[test]
+    fn clip_segment_trims_to_the_inset_outline() {
+        for shape in SHAPES {
+            let outline = outline(shape);
+            let (a, b) = (Vector::new(-50.0, 1.0), Vector::new(50.0, 1.0));
+            let (start, end) = outline.clip_segment(a, b, 2.0).expect("crosses the eye");
+            assert!(start.x < 0.0 && end.x > 0.0);
+            for point in [start, end] {
+                assert!((point.y - 1.0).abs() < 1e-4);
+                assert!((outline.depth(point) - 2.0).abs() < 1e-3, "{shape:?} {point:?}");
+            }
+
+            let (a, b) = (Vector::new(-50.0, 40.0), Vector::new(50.0, 40.0));
+            assert_eq!(outline.clip_segment(a, b, 0.0), None);
+        }
+        // Fully inside: untouched.
+        let outline = outline(EyeShape::Circle);
+        let (a, b) = (Vector::new(-3.0, 2.0), Vector::new(4.0, -1.0));
+        assert_eq!(outline.clip_segment(a, b, 1.0), Some((a, b)));
+    }
+}
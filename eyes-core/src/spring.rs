@@ -0,0 +1,110 @@
+use crate::geometry::Vector;
+
+/// Fixed integration step, so the motion doesn't depend on the tick rate.
+const STEP_SECS: f32 = 1.0 / 240.0;
+/// Longer gaps (e.g. after suspend) are cut short instead of simulated.
+const MAX_FRAME_SECS: f32 = 0.25;
+/// Below these a point counts as resting: further motion wouldn't show.
+const SETTLED_DISTANCE: f32 = 0.01;
+const SETTLED_SPEED: f32 = 0.1;
+
+/// Damped spring pulling a point toward its target.
+#[derive(Debug, Clone, Copy)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl Spring {
+    /// Spring with natural `frequency` (rad/s) and `damping_ratio` (`1.0` is critically
+    /// damped, lower values overshoot a little before settling).
+    pub fn from_response(frequency: f32, damping_ratio: f32) -> Self {
+        Self {
+            stiffness: frequency * frequency,
+            damping: 2.0 * damping_ratio * frequency,
+        }
+    }
+}
+
+/// Position and velocity of one spring-driven point.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Motion {
+    pub position: Vector,
+    pub velocity: Vector,
+}
+
+impl Motion {
+    /// Whether the point rests at `target`.
+    pub fn is_settled(&self, target: Vector) -> bool {
+        let offset = target - self.position;
+        offset.x.hypot(offset.y) < SETTLED_DISTANCE
+            && self.velocity.x.hypot(self.velocity.y) < SETTLED_SPEED
+    }
+
+    fn step(&mut self, target: Vector, spring: Spring) {
+        // Semi-implicit Euler: stable for the stiffness range we use at this step size.
+        let accel = (target - self.position) * spring.stiffness - self.velocity * spring.damping;
+        self.velocity = self.velocity + accel * STEP_SECS;
+        self.position = self.position + self.velocity * STEP_SECS;
+    }
+}
+
+/// Splits variable frame times into fixed integration steps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stepper {
+    accumulator: f32,
+}
+
+impl Stepper {
+    /// Number of fixed steps to run for a frame of `dt` seconds.
+    pub fn steps(&mut self, dt: f32) -> usize {
+        self.accumulator += dt.clamp(0.0, MAX_FRAME_SECS);
+        let steps = (self.accumulator / STEP_SECS).floor();
+        self.accumulator -= steps * STEP_SECS;
+        steps as usize
+    }
+}
+
+/// Advances `motion` toward `target` by `steps` fixed steps.
+pub fn advance(motion: &mut Motion, target: Vector, spring: Spring, steps: usize) {
+    for _ in 0..steps {
+        motion.step(target, spring);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs one second of frames `dt` apart toward `target` and returns the motion.
+    fn run(dt: f32, target: Vector) -> Motion {
+        let spring = Spring::from_response(18.0, 0.7);
+        let mut motion = Motion::default();
+        let mut stepper = Stepper::default();
+        let mut elapsed = 0.0;
+        while elapsed < 1.0 - 1e-4 {
+            advance(&mut motion, target, spring, stepper.steps(dt));
+            elapsed += dt;
+        }
+        motion
+    }
+
+    #[test]
+    fn settles_the_same_at_any_frame_rate() {
+        let target = Vector::new(6.0, -3.0);
+        let reference = run(1.0 / 240.0, target);
+        for dt in [1.0 / 30.0, 1.0 / 60.0, 1.0 / 144.0] {
+            let motion = run(dt, target);
+            assert!((motion.position - reference.position).length() < 0.05, "{dt}: {motion:?}");
+            assert!(motion.is_settled(target), "{dt}: {motion:?}");
+        }
+    }
+
+    #[test]
+    fn long_frames_are_cut_short() {
+        let mut stepper = Stepper::default();
+        let simulated = stepper.steps(10.0) as f32 * STEP_SECS;
+        assert!((simulated - MAX_FRAME_SECS).abs() <= STEP_SECS, "{simulated}");
+        assert_eq!(stepper.steps(-1.0), 0);
+    }
+}
@@ -0,0 +1,78 @@
+//! Drawing a frame while the pupils follow the cursor: the whole scene from scratch, against
+//! the applet's caching, which keeps the sclera, shading and outlines and only draws the pupils,
+//! lids and brows each frame.
+//!
+//! This draws on the CPU with tiny-skia, so it shows what the cached layers save in building
+//! and rasterizing geometry; on the GPU the applet saves tessellation on top.
+//!
+//! `cargo bench -p eyes-render --bench draw`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use eyes_core::{Arrangement, Color, Eyes, Item, Palette, Point, Scene, Size, Style};
+use eyes_render::{paint, pixmap_for, Pixmap};
+use tiny_skia::{PixmapPaint, Transform};
+
+/// A panel-sized applet on a HiDPI screen.
+const SIZE: Size = Size::new(96.0, 40.0);
+const SCALE_FACTOR: f32 = 2.0;
+
+fn palette() -> Palette {
+    Palette::new(true, Color::from_rgb8(0x63, 0xd0, 0xdf), None)
+}
+
+/// Moves the cursor one frame further around the eyes, as while following it.
+fn step(eyes: &mut Eyes, frame: &mut u32) {
+    let angle = *frame as f32 * 0.05;
+    *frame += 1;
+    let cursor = Point::new(48.0 + 200.0 * angle.cos(), 20.0 + 200.0 * angle.sin());
+    eyes.tick(Some(cursor), SIZE, 1.0 / 60.0);
+}
+
+fn scene(eyes: &Eyes) -> Scene<'_> {
+    Scene::new(eyes, SIZE, palette(), Style::Classic, true)
+}
+
+fn draw(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame");
+
+    let mut eyes = Eyes::new();
+    eyes.set_arrangement(Arrangement::Horizontal);
+    let mut frame = 0;
+    group.bench_function("full_scene", |b| {
+        b.iter(|| {
+            step(&mut eyes, &mut frame);
+            let mut pixmap = pixmap_for(SIZE, SCALE_FACTOR).expect("non-empty size");
+            paint(&mut pixmap, &scene(&eyes).items(), SCALE_FACTOR);
+            pixmap
+        });
+    });
+
+    let mut eyes = Eyes::new();
+    eyes.set_arrangement(Arrangement::Horizontal);
+    let mut frame = 0;
+    // The layers don't depend on where the pupils are, so they are drawn once.
+    let layer = |items: Vec<Item>| {
+        let mut pixmap = pixmap_for(SIZE, SCALE_FACTOR).expect("non-empty size");
+        paint(&mut pixmap, &items, SCALE_FACTOR);
+        pixmap
+    };
+    let background = layer(scene(&eyes).background());
+    let outline = layer(scene(&eyes).outline());
+    group.bench_function("cached_layers", |b| {
+        b.iter(|| {
+            step(&mut eyes, &mut frame);
+            let scene = scene(&eyes);
+            let mut pixmap: Pixmap = background.clone();
+            paint(&mut pixmap, &scene.moving_parts(), SCALE_FACTOR);
+            let identity = Transform::identity();
+            pixmap.draw_pixmap(0, 0, outline.as_ref(), &PixmapPaint::default(), identity, None);
+            paint(&mut pixmap, &scene.brows(), SCALE_FACTOR);
+            pixmap
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, draw);
+criterion_main!(benches);
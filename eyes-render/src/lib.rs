@@ -0,0 +1,93 @@
+//! Draws an [`eyes_core::Scene`] with tiny-skia, without a GUI or a GPU.
+//!
+//! The applet puts the same scene items on its COSMIC canvas, so a pixmap from here shows
+//! what the panel shows. It backs the golden-image tests and is handy for screenshots:
+//! render, then `Pixmap::save_png` or `Pixmap::encode_png`.
+
+use eyes_core::{Color, Item, Paint, Point, Scene, Shape, Size, Stroke};
+use tiny_skia::{FillRule, LineCap, LineJoin, PathBuilder, Transform};
+
+pub use tiny_skia::Pixmap;
+
+/// Renders `scene` at `scale_factor` device pixels per logical pixel on a transparent
+/// background. `None` if that is less than one pixel wide or high.
+pub fn render(scene: &Scene, scale_factor: f32) -> Option<Pixmap> {
+    let mut pixmap = pixmap_for(scene.size(), scale_factor)?;
+    paint(&mut pixmap, &scene.items(), scale_factor);
+    Some(pixmap)
+}
+
+/// A transparent pixmap covering `size` logical pixels at `scale_factor`.
+pub fn pixmap_for(size: Size, scale_factor: f32) -> Option<Pixmap> {
+    let width = (size.width * scale_factor).ceil();
+    let height = (size.height * scale_factor).ceil();
+    Pixmap::new(width as u32, height as u32)
+}
+
+/// Draws scene `items` (in logical pixels) in order onto `pixmap`.
+pub fn paint(pixmap: &mut Pixmap, items: &[Item], scale_factor: f32) {
+    let transform = Transform::from_scale(scale_factor, scale_factor);
+    for item in items {
+        let Some(path) = path(&item.shape) else {
+            continue;
+        };
+        match item.paint {
+            Paint::Fill(color) => {
+                pixmap.fill_path(&path, &solid(color), FillRule::Winding, transform, None);
+            }
+            Paint::Stroke(stroke) => {
+                let paint = solid(stroke.color);
+                pixmap.stroke_path(&path, &paint, &skia_stroke(stroke), transform, None);
+            }
+        }
+    }
+}
+
+/// `None` for shapes too degenerate to draw.
+fn path(shape: &Shape) -> Option<tiny_skia::Path> {
+    let polyline = |builder: &mut PathBuilder, points: &[Point]| {
+        for (i, point) in points.iter().enumerate() {
+            if i == 0 {
+                builder.move_to(point.x, point.y);
+            } else {
+                builder.line_to(point.x, point.y);
+            }
+        }
+    };
+    let mut builder = PathBuilder::new();
+    match shape {
+        Shape::Polygon(points) => {
+            polyline(&mut builder, points);
+            builder.close();
+        }
+        Shape::Polyline(points) => polyline(&mut builder, points),
+        Shape::Segments(segments) => {
+            for (a, b) in segments {
+                builder.move_to(a.x, a.y);
+                builder.line_to(b.x, b.y);
+            }
+        }
+        Shape::Circle { center, radius } => {
+            return PathBuilder::from_circle(center.x, center.y, *radius);
+        }
+    }
+    builder.finish()
+}
+
+fn solid(color: Color) -> tiny_skia::Paint<'static> {
+    let [r, g, b, a] = [color.r, color.g, color.b, color.a].map(|value| value.clamp(0.0, 1.0));
+    let color = tiny_skia::Color::from_rgba(r, g, b, a).unwrap_or(tiny_skia::Color::TRANSPARENT);
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(color);
+    paint.anti_alias = true;
+    paint
+}
+
+fn skia_stroke(stroke: Stroke) -> tiny_skia::Stroke {
+    tiny_skia::Stroke {
+        width: stroke.width,
+        line_cap: if stroke.round_cap { LineCap::Round } else { LineCap::Butt },
+        line_join: if stroke.round_join { LineJoin::Round } else { LineJoin::Miter },
+        ..tiny_skia::Stroke::default()
+    }
+}
@@ -0,0 +1,263 @@
+//! Golden-image tests: every case is rendered headlessly and compared with the PNG of the same
+//! name in `tests/golden`, and checked for anything drawn outside the eye outlines.
+//!
+//! After an intended change in the drawing, look at the new images and bless them with
+//! `EYES_BLESS=1 cargo test -p eyes-render`. Mismatches are written next to the build's
+//! temporary files so they can be compared with the golden ones.
+
+use eyes_core::{
+    Arrangement, Color, Expression, EyeShape, Eyes, Gaze, Item, Paint, Palette, Point, Scene,
+    Settings, Shape, Size, Style,
+};
+use eyes_render::{paint, pixmap_for, render, Pixmap};
+
+use std::path::PathBuf;
+
+/// 1.5 s at 60 fps: long enough for the pupils, lids and brows to get where they are going,
+/// short enough that no blink (at least 2 s away) has started.
+const SETTLE_FRAMES: u32 = 90;
+/// Largest per-channel difference tolerated, for float differences between platforms.
+const TOLERANCE: u8 = 2;
+/// Outside the outlines, coverage up to this alpha is tolerated at scale 1: strokes one device
+/// pixel wide are drawn as anti-aliased hairlines, which smear a faint fringe past their
+/// geometry.
+const HAIRLINE_FRINGE: u8 = 16;
+
+struct Case {
+    name: &'static str,
+    size: Size,
+    scale_factor: f32,
+    arrangement: Arrangement,
+    /// `None` while the cursor position is unknown.
+    cursor: Option<Point>,
+    expression: Option<Expression>,
+    settings: Settings,
+    style: Style,
+    dark: bool,
+}
+
+impl Default for Case {
+    fn default() -> Self {
+        Self {
+            name: "",
+            size: Size::new(96.0, 40.0),
+            scale_factor: 1.0,
+            arrangement: Arrangement::Horizontal,
+            cursor: None,
+            expression: None,
+            settings: Settings::default(),
+            style: Style::Classic,
+            dark: true,
+        }
+    }
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            name: "horizontal_96x40",
+            cursor: Some(Point::new(300.0, 120.0)),
+            ..Case::default()
+        },
+        Case {
+            name: "horizontal_96x40_scale2",
+            scale_factor: 2.0,
+            cursor: Some(Point::new(300.0, 120.0)),
+            ..Case::default()
+        },
+        Case {
+            name: "horizontal_96x40_unknown",
+            ..Case::default()
+        },
+        Case {
+            name: "vertical_40x96",
+            size: Size::new(40.0, 96.0),
+            arrangement: Arrangement::Vertical,
+            cursor: Some(Point::new(-200.0, -50.0)),
+            ..Case::default()
+        },
+        Case {
+            name: "vertical_40x96_unknown_light",
+            size: Size::new(40.0, 96.0),
+            arrangement: Arrangement::Vertical,
+            dark: false,
+            ..Case::default()
+        },
+        Case {
+            name: "small_24x24_scale1_5",
+            size: Size::new(24.0, 24.0),
+            scale_factor: 1.5,
+            cursor: Some(Point::new(12.0, 400.0)),
+            ..Case::default()
+        },
+        Case {
+            name: "large_160x64_flat_light",
+            size: Size::new(160.0, 64.0),
+            cursor: Some(Point::new(80.0, 20.0)),
+            style: Style::Flat,
+            dark: false,
+            ..Case::default()
+        },
+        // Biggest pupils: large ratio, relaxed by a distant cursor and widened by the mood.
+        Case {
+            name: "pupil_largest",
+            size: Size::new(128.0, 48.0),
+            cursor: Some(Point::new(-2000.0, -2000.0)),
+            expression: Some(Expression::Happy),
+            settings: Settings { pupil_ratio: 0.8, ..Settings::default() },
+            ..Case::default()
+        },
+        // Smallest pupils: small ratio, constricted by a close cursor and a startled look.
+        Case {
+            name: "pupil_smallest",
+            size: Size::new(128.0, 48.0),
+            cursor: Some(Point::new(64.0, 30.0)),
+            expression: Some(Expression::Surprised),
+            settings: Settings { pupil_ratio: 0.1, ..Settings::default() },
+            ..Case::default()
+        },
+        // Pupils pushed into the corners of pointed and tall eyes, foreshortened as eyeballs.
+        Case {
+            name: "pupil_edge_almond_eyeball_scale2",
+            size: Size::new(128.0, 48.0),
+            scale_factor: 2.0,
+            cursor: Some(Point::new(1000.0, -1000.0)),
+            settings: Settings {
+                shape: EyeShape::Almond,
+                gaze: Gaze::Eyeball,
+                ..Settings::default()
+            },
+            ..Case::default()
+        },
+        // The outline's stroke along the almond's pointed corners, where it used to poke out.
+        Case {
+            name: "almond_corners_scale2",
+            scale_factor: 2.0,
+            cursor: Some(Point::new(48.0, 20.0)),
+            settings: Settings { shape: EyeShape::Almond, ..Settings::default() },
+            ..Case::default()
+        },
+        Case {
+            name: "pupil_edge_ellipse",
+            size: Size::new(96.0, 64.0),
+            cursor: Some(Point::new(-1000.0, 1000.0)),
+            settings: Settings { shape: EyeShape::Ellipse, ..Settings::default() },
+            ..Case::default()
+        },
+        Case {
+            name: "angry_rounded_rect",
+            size: Size::new(96.0, 48.0),
+            cursor: Some(Point::new(48.0, 200.0)),
+            expression: Some(Expression::Angry),
+            settings: Settings { shape: EyeShape::RoundedRect, ..Settings::default() },
+            ..Case::default()
+        },
+    ]
+}
+
+fn settled_eyes(case: &Case) -> Eyes {
+    let mut eyes = Eyes::new();
+    eyes.set_settings(case.settings.clone());
+    eyes.set_arrangement(case.arrangement);
+    eyes.set_expression(case.expression);
+    for _ in 0..SETTLE_FRAMES {
+        eyes.tick(case.cursor, case.size, 1.0 / 60.0);
+    }
+    eyes
+}
+
+fn palette(dark: bool) -> Palette {
+    // COSMIC's default accent.
+    Palette::new(dark, Color::from_rgb8(0x63, 0xd0, 0xdf), None)
+}
+
+fn scene<'a>(case: &Case, eyes: &'a Eyes) -> Scene<'a> {
+    Scene::new(eyes, case.size, palette(case.dark), case.style, true)
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// Where a differing image ends up for comparison.
+fn actual_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.actual.png"))
+}
+
+/// Number of pixels differing by more than `TOLERANCE` in any channel.
+fn differing_pixels(a: &Pixmap, b: &Pixmap) -> usize {
+    a.data()
+        .chunks_exact(4)
+        .zip(b.data().chunks_exact(4))
+        .filter(|(a, b)| a.iter().zip(b.iter()).any(|(a, b)| a.abs_diff(*b) > TOLERANCE))
+        .count()
+}
+
+#[test]
+fn golden_images() {
+    let bless = std::env::var_os("EYES_BLESS").is_some();
+    let mut failures = Vec::new();
+
+    for case in cases() {
+        let eyes = settled_eyes(&case);
+        let pixmap = render(&scene(&case, &eyes), case.scale_factor).expect("non-empty size");
+        let golden = golden_dir().join(format!("{}.png", case.name));
+
+        if bless {
+            pixmap.save_png(&golden).expect("write golden image");
+            continue;
+        }
+        let Ok(expected) = Pixmap::load_png(&golden) else {
+            failures.push(format!("{}: no golden image (bless with EYES_BLESS=1)", case.name));
+            continue;
+        };
+        let matches = (expected.width(), expected.height()) == (pixmap.width(), pixmap.height())
+            && differing_pixels(&expected, &pixmap) == 0;
+        if !matches {
+            let actual = actual_path(case.name);
+            pixmap.save_png(&actual).expect("write actual image");
+            failures.push(format!("{}: differs, see {}", case.name, actual.display()));
+        }
+    }
+
+    assert!(failures.is_empty(), "golden images:\n{}", failures.join("\n"));
+}
+
+/// Nothing but the brows may be drawn where the eye outlines don't reach: anything visible
+/// there "bleeds" out, like a highlight or an iris that wasn't kept inside the sclera, or an
+/// outline stroke poking out of a pointed corner.
+#[test]
+fn nothing_bleeds_outside_the_outline() {
+    for case in cases() {
+        let eyes = settled_eyes(&case);
+        let scene = scene(&case, &eyes);
+
+        let mut drawn = pixmap_for(case.size, case.scale_factor).expect("non-empty size");
+        let mut items = scene.background();
+        items.extend(scene.moving_parts());
+        items.extend(scene.outline());
+        paint(&mut drawn, &items, case.scale_factor);
+
+        let mut mask = pixmap_for(case.size, case.scale_factor).expect("non-empty size");
+        let outlines: Vec<Item> = scene
+            .centers()
+            .iter()
+            .map(|&center| Item {
+                shape: Shape::Polygon(
+                    scene.layout().outline.points().iter().map(|&point| center + point).collect(),
+                ),
+                paint: Paint::Fill(Color::WHITE),
+            })
+            .collect();
+        paint(&mut mask, &outlines, case.scale_factor);
+
+        let fringe = if case.scale_factor <= 1.0 { HAIRLINE_FRINGE } else { 0 };
+        let bleeding = drawn
+            .pixels()
+            .iter()
+            .zip(mask.pixels())
+            .filter(|(drawn, mask)| mask.alpha() == 0 && drawn.alpha() > fringe)
+            .count();
+        assert_eq!(bleeding, 0, "{}: {bleeding} pixels outside the outline", case.name);
+    }
+}
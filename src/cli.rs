@@ -0,0 +1,216 @@
+//! `cosmic-ext-eyes calibration ...`: look at and edit the saved global-to-local offsets
+//! without deleting state files by hand.
+
+use cosmic::iced::Vector;
+
+use crate::persist::{self, Calibration};
+
+const USAGE: &str = "\
+usage: cosmic-ext-eyes calibration <command>
+
+commands:
+  list                               every saved calibration; * marks this environment's
+  show                               the keys this environment resolves to and its calibration
+  reset [--output X] [--instance Y]  forget this environment's calibration, or every one
+        [--all]                      matching the given keys, or all of them
+  set <x> <y> --scale <s>            save an offset for this environment (or the given keys)
+        [--output X] [--instance Y]
+
+The output key comes from COSMIC_PANEL_OUTPUT; the instance key from COSMIC_EYES_OFFSET_KEY,
+or else from the COSMIC_APPLET_*, COSMIC_PANEL_* and COSMIC_DOCK_* variables the panel sets.";
+
+enum Error {
+    /// Bad arguments; the usage is printed.
+    Usage(String),
+    Failed(String),
+}
+
+#[derive(Default)]
+struct Options {
+    positional: Vec<String>,
+    output: Option<String>,
+    instance: Option<String>,
+    scale: Option<f32>,
+    all: bool,
+}
+
+impl Options {
+    /// Parses `args`, accepting only the `allowed` options.
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Self, Error> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            // Anything not starting with `--` is positional, so negative offsets work.
+            if !arg.starts_with("--") {
+                options.positional.push(arg.clone());
+                continue;
+            }
+            if !allowed.contains(&arg.as_str()) {
+                return Err(Error::Usage(format!("unexpected option {arg}")));
+            }
+            let mut value = || {
+                args.next().ok_or_else(|| Error::Usage(format!("{arg} needs a value")))
+            };
+            match arg.as_str() {
+                "--output" => options.output = Some(persist::sanitize_key(value()?)),
+                "--instance" => options.instance = Some(persist::sanitize_key(value()?)),
+                "--scale" => {
+                    let raw = value()?;
+                    options.scale = Some(
+                        raw.parse()
+                            .ok()
+                            .filter(|scale: &f32| scale.is_finite() && *scale > 0.0)
+                            .ok_or_else(|| Error::Usage(format!("invalid scale {raw}")))?,
+                    );
+                }
+                "--all" => options.all = true,
+                _ => unreachable!("allowed options are handled above"),
+            }
+        }
+        Ok(options)
+    }
+
+    fn no_positional(self) -> Result<Self, Error> {
+        match self.positional.first() {
+            Some(arg) => Err(Error::Usage(format!("unexpected argument {arg}"))),
+            None => Ok(self),
+        }
+    }
+}
+
+/// Runs `cosmic-ext-eyes calibration <args>` and returns the process exit code.
+pub fn calibration(args: &[String]) -> i32 {
+    let Some((command, args)) = args.split_first() else {
+        println!("{USAGE}");
+        return 0;
+    };
+    let result = match command.as_str() {
+        "list" => Options::parse(args, &[]).and_then(Options::no_positional).and_then(list),
+        "show" => Options::parse(args, &[]).and_then(Options::no_positional).and_then(show),
+        "reset" => Options::parse(args, &["--output", "--instance", "--all"])
+            .and_then(Options::no_positional)
+            .and_then(reset),
+        "set" => Options::parse(args, &["--output", "--instance", "--scale"]).and_then(set),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(Error::Usage(format!("unknown command {command}"))),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(Error::Usage(message)) => {
+            eprintln!("{message}\n\n{USAGE}");
+            2
+        }
+        Err(Error::Failed(message)) => {
+            eprintln!("{message}");
+            1
+        }
+    }
+}
+
+fn describe(calibration: &Calibration) -> String {
+    let Calibration { scale, offset } = calibration;
+    format!("scale {scale}, offset ({}, {})", offset.x, offset.y)
+}
+
+fn print_keys() {
+    println!("output key:   {}", persist::output_key());
+    println!("instance key: {}", persist::instance_key());
+}
+
+fn list(_: Options) -> Result<(), Error> {
+    let files = persist::offset_files()
+        .map_err(|err| Error::Failed(format!("failed to list calibrations: {err}")))?;
+    let current = persist::current_calibration().map(|(path, _)| path);
+
+    print_keys();
+    if files.is_empty() {
+        println!("no saved calibrations");
+        return Ok(());
+    }
+    let width = files.iter().filter_map(|path| persist::offset_key(path)).map(str::len).max();
+    for path in &files {
+        let marker = if current.as_deref() == Some(path.as_path()) { '*' } else { ' ' };
+        let key = persist::offset_key(path).unwrap_or_default();
+        let calibration = Calibration::read(path)
+            .map_or_else(|| "unreadable".to_string(), |calibration| describe(&calibration));
+        println!("{marker} {key:<width$}  {calibration}", width = width.unwrap_or_default());
+    }
+    Ok(())
+}
+
+fn show(_: Options) -> Result<(), Error> {
+    print_keys();
+    let path = persist::offset_path()
+        .ok_or_else(|| Error::Failed("no state directory (HOME is not set)".to_string()))?;
+    println!("file:         {}", path.display());
+    match persist::current_calibration() {
+        Some((used, calibration)) => {
+            println!("calibration:  {}", describe(&calibration));
+            if used != path {
+                println!("              (from the older file {})", used.display());
+            }
+        }
+        None => println!("calibration:  none; hover over the eyes once to calibrate"),
+    }
+    Ok(())
+}
+
+fn reset(options: Options) -> Result<(), Error> {
+    let files = if options.all || options.output.is_some() || options.instance.is_some() {
+        let output = options.output.as_deref();
+        let instance = options.instance.as_deref();
+        persist::offset_files()
+            .map_err(|err| Error::Failed(format!("failed to list calibrations: {err}")))?
+            .into_iter()
+            .filter(|path| {
+                persist::offset_key(path)
+                    .is_some_and(|key| persist::key_matches(key, output, instance))
+            })
+            .collect()
+    } else {
+        // The older files would take over if they were left behind.
+        [persist::offset_path(), persist::dashed_offset_path(), persist::legacy_offset_path()]
+            .into_iter()
+            .flatten()
+            .filter(|path| path.exists())
+            .collect::<Vec<_>>()
+    };
+
+    if files.is_empty() {
+        println!("nothing to reset");
+    }
+    for path in files {
+        std::fs::remove_file(&path)
+            .map_err(|err| Error::Failed(format!("failed to remove {}: {err}", path.display())))?;
+        println!("removed {}", path.display());
+    }
+    Ok(())
+}
+
+fn set(options: Options) -> Result<(), Error> {
+    let [x, y] = options.positional.as_slice() else {
+        return Err(Error::Usage("set needs an x and a y offset".to_string()));
+    };
+    let parse = |value: &String| {
+        value
+            .parse::<f32>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| Error::Usage(format!("invalid offset {value}")))
+    };
+    let offset = Vector::new(parse(x)?, parse(y)?);
+    // Offsets only apply at the scale they were measured at, so guessing one would be a trap.
+    let scale = options.scale.ok_or_else(|| Error::Usage("set needs --scale".to_string()))?;
+
+    let output = options.output.unwrap_or_else(persist::output_key);
+    let instance = options.instance.unwrap_or_else(persist::instance_key);
+    let path = persist::offset_path_for(&output, &instance)
+        .ok_or_else(|| Error::Failed("no state directory (HOME is not set)".to_string()))?;
+    persist::save_offset_to(&path, scale, offset)
+        .map_err(|err| Error::Failed(format!("failed to write {}: {err}", path.display())))?;
+    println!("{}: {}", path.display(), describe(&Calibration { scale, offset }));
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use eyes_core::{EyeShape, Settings};
+use serde::{Deserialize, Serialize};
+
+pub use eyes_core::{Gaze, Style};
+
+/// Layer-shell layer of the desktop overlay, from just above the wallpaper to above
+/// everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlayLayer {
+    Background,
+    /// Above the wallpaper, below windows.
+    #[default]
+    Bottom,
+    /// Above windows.
+    Top,
+    /// Above everything, even fullscreen windows.
+    Overlay,
+}
+
+/// Where the desktop overlay sits on its output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlayAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// User settings, stored with `cosmic-config` under the applet's `APP_ID`.
+///
+/// Shared by every running instance; each one watches for changes and applies them live.
+#[derive(Debug, Clone, CosmicConfigEntry, PartialEq)]
+#[version = 1]
+pub struct Config {
+    /// Smallest eye radius in logical pixels.
+    pub min_eye_radius: f32,
+    /// Largest eye radius in logical pixels.
+    pub max_eye_radius: f32,
+    /// Pupil radius as a fraction of the eye radius.
+    pub pupil_ratio: f32,
+    /// How quickly the pupils catch up with the cursor: the spring's natural frequency (rad/s).
+    pub follow_speed: f32,
+    /// Damping ratio of the pupil spring: `1.0` settles without overshoot, lower values
+    /// overshoot slightly.
+    pub follow_damping: f32,
+    /// Pupils constrict when the cursor is close or moving fast and relax when it's far.
+    pub pupil_dilation: bool,
+    /// Pupils are a little wider on a dark theme.
+    pub pupil_theme_response: bool,
+    /// Animate on the compositor's frame callbacks; `tick_rate` is used otherwise.
+    pub sync_to_frames: bool,
+    /// Animation ticks per second when not synced to frames.
+    pub tick_rate: u32,
+    /// Follow the cursor outside the applet (needs COSMIC's privileged applet socket).
+    pub global_tracking: bool,
+    pub style: Style,
+    pub shape: EyeShape,
+    /// Draw a colored iris around the pupil.
+    pub iris: bool,
+    /// Iris color as RGB; `None` follows the theme's accent color.
+    pub iris_color: Option<[u8; 3]>,
+    /// Fine radial lines in the iris.
+    pub iris_striations: bool,
+    pub gaze: Gaze,
+    /// Virtual distance of the cursor in front of the screen for `Gaze::Eyeball`, in logical
+    /// pixels. Smaller values make the eyes cross more when the cursor is close.
+    pub cursor_depth: f32,
+    /// Seconds without a known cursor before the eyes start looking around on their own.
+    pub wander_after_secs: f32,
+    /// Seconds without a known cursor before the eyes fall asleep.
+    pub sleep_after_secs: f32,
+    /// Layer of the desktop overlay (`--overlay`).
+    pub overlay_layer: OverlayLayer,
+    pub overlay_anchor: OverlayAnchor,
+    /// Overlay width and height in logical pixels.
+    pub overlay_size: (u32, u32),
+    /// Overlay distance from the output edges it is anchored to, in logical pixels, as
+    /// `(top, right, bottom, left)`.
+    pub overlay_margin: (i32, i32, i32, i32),
+    /// Output (connector name, e.g. `DP-1`) the overlay is on; `None`, or an output that isn't
+    /// connected, puts it on the first one.
+    pub overlay_output: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            min_eye_radius: 6.0,
+            max_eye_radius: 32.0,
+            pupil_ratio: 0.38,
+            follow_speed: 12.0,
+            follow_damping: 0.8,
+            pupil_dilation: true,
+            pupil_theme_response: false,
+            sync_to_frames: true,
+            tick_rate: 60,
+            global_tracking: true,
+            style: Style::Classic,
+            shape: EyeShape::Circle,
+            iris: true,
+            iris_color: None,
+            iris_striations: true,
+            gaze: Gaze::Flat,
+            cursor_depth: 150.0,
+            wander_after_secs: 8.0,
+            sleep_after_secs: 90.0,
+            overlay_layer: OverlayLayer::Bottom,
+            overlay_anchor: OverlayAnchor::Center,
+            overlay_size: (480, 240),
+            overlay_margin: (0, 0, 0, 0),
+            overlay_output: None,
+        }
+    }
+}
+
+impl Config {
+    /// Opens the settings store; `None` if it isn't available (settings then stay in memory).
+    pub fn handler(app_id: &str) -> Option<cosmic_config::Config> {
+        match cosmic_config::Config::new(app_id, Self::VERSION) {
+            Ok(handler) => Some(handler),
+            Err(err) => {
+                tracing::warn!("failed to open config: {err}");
+                None
+            }
+        }
+    }
+
+    /// Loads the stored settings, falling back to defaults for anything missing or invalid.
+    pub fn load(handler: &cosmic_config::Config) -> Self {
+        match Self::get_entry(handler) {
+            Ok(config) => config,
+            Err((errors, config)) => {
+                for err in errors {
+                    // Missing keys on a fresh install are expected.
+                    if err.is_err() {
+                        tracing::warn!("failed to load config: {err}");
+                    }
+                }
+                config
+            }
+        }
+    }
+
+    pub fn save(&self, handler: &cosmic_config::Config) {
+        if let Err(err) = self.write_entry(handler) {
+            tracing::warn!("failed to save config: {err}");
+        }
+    }
+
+    /// What the eye model needs, with every value clamped.
+    pub fn settings(&self) -> Settings {
+        Settings {
+            eye_radius_range: self.eye_radius_range(),
+            pupil_ratio: self.pupil_ratio(),
+            shape: self.shape,
+            iris: self.iris,
+            gaze: self.gaze,
+            cursor_depth: self.cursor_depth(),
+            follow_speed: self.follow_speed(),
+            follow_damping: self.follow_damping(),
+            pupil_dilation: self.pupil_dilation,
+            pupil_theme_response: self.pupil_theme_response,
+            wander_after_secs: self.wander_after_secs(),
+            sleep_after_secs: self.sleep_after_secs(),
+        }
+    }
+
+    /// `(min, max)` eye radius, ordered and kept within sane bounds.
+    pub fn eye_radius_range(&self) -> (f32, f32) {
+        let min = self.min_eye_radius.clamp(2.0, 256.0);
+        let max = self.max_eye_radius.clamp(min, 256.0);
+        (min, max)
+    }
+
+    pub fn pupil_ratio(&self) -> f32 {
+        self.pupil_ratio.clamp(0.1, 0.8)
+    }
+
+    pub fn follow_speed(&self) -> f32 {
+        self.follow_speed.clamp(0.5, 60.0)
+    }
+
+    pub fn follow_damping(&self) -> f32 {
+        self.follow_damping.clamp(0.2, 2.0)
+    }
+
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate.clamp(10, 240)
+    }
+
+    pub fn cursor_depth(&self) -> f32 {
+        self.cursor_depth.clamp(10.0, 10_000.0)
+    }
+
+    pub fn wander_after_secs(&self) -> f32 {
+        self.wander_after_secs.max(0.0)
+    }
+
+    /// Never before the eyes have started wandering.
+    pub fn sleep_after_secs(&self) -> f32 {
+        self.sleep_after_secs.max(self.wander_after_secs())
+    }
+
+    pub fn overlay_size(&self) -> (u32, u32) {
+        let (width, height) = self.overlay_size;
+        (width.clamp(16, 16_384), height.clamp(16, 16_384))
+    }
+}
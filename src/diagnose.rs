@@ -0,0 +1,210 @@
+//! `cosmic-ext-eyes --diagnose [--json]`: explains why global tracking does or doesn't work.
+
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::global_cursor::{self, Probe, SessionReport, Update};
+use crate::persist;
+
+/// Environment variables that decide how tracking is set up and where calibration is kept.
+const VARIABLES: [&str; 7] = [
+    "X_PRIVILEGED_WAYLAND_SOCKET",
+    "COSMIC_EYES_DISABLE_GLOBAL_CURSOR",
+    "COSMIC_PANEL_OUTPUT",
+    "COSMIC_EYES_OFFSET_KEY",
+    "WAYLAND_DISPLAY",
+    "XDG_STATE_HOME",
+    "HOME",
+];
+/// Cursor positions to wait for, and for how long at most.
+const SAMPLES: usize = 5;
+const SAMPLE_TIME: Duration = Duration::from_secs(3);
+
+#[derive(Serialize)]
+struct Report {
+    environment: BTreeMap<&'static str, Option<String>>,
+    /// Why the applet wouldn't start global tracking here; `None` if it would.
+    tracking_unavailable: Option<String>,
+    persistence: Persistence,
+    wayland: Probe,
+}
+
+#[derive(Serialize)]
+struct Persistence {
+    output_key: String,
+    instance_key: String,
+    state_dir: Option<PathBuf>,
+    offset_file: Option<PathBuf>,
+    legacy_offset_file: Option<PathBuf>,
+    /// The offset saved for this environment, and the file it comes from.
+    saved: Option<SavedOffset>,
+}
+
+#[derive(Serialize)]
+struct SavedOffset {
+    file: PathBuf,
+    /// The scale factor it was measured at, the only one it applies at.
+    scale: f32,
+    x: f32,
+    y: f32,
+    /// Scale factor of the output the applet is on, if the probe found it.
+    output_scale: Option<f32>,
+    /// Whether the applet loads the offset at `output_scale`.
+    loaded: Option<bool>,
+}
+
+/// Gathers the report and prints it; returns the process exit code.
+pub fn run(json: bool) -> i32 {
+    if !json {
+        println!("Probing Wayland; move the cursor for a few seconds...\n");
+    }
+    let wayland = global_cursor::probe(SAMPLES, SAMPLE_TIME);
+    // Saved offsets only apply at the scale they were measured at.
+    let home = wayland.outputs.iter().find(|output| output.home);
+    let output_scale = home.and_then(|output| output.scale_factor);
+    let report = Report {
+        environment: VARIABLES.map(|name| (name, std::env::var(name).ok())).into(),
+        tracking_unavailable: global_cursor::privileged_socket()
+            .err()
+            .map(|reason| reason.to_string()),
+        persistence: Persistence {
+            output_key: persist::output_key(),
+            instance_key: persist::instance_key(),
+            state_dir: persist::app_state_dir(),
+            offset_file: persist::offset_path(),
+            legacy_offset_file: persist::legacy_offset_path(),
+            saved: persist::current_calibration().map(|(file, calibration)| SavedOffset {
+                file,
+                scale: calibration.scale,
+                x: calibration.offset.x,
+                y: calibration.offset.y,
+                output_scale,
+                loaded: output_scale.map(|scale| calibration.applies_at(scale)),
+            }),
+        },
+        wayland,
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("failed to write the report: {err}");
+                return 1;
+            }
+        }
+    } else {
+        print(&report);
+    }
+    0
+}
+
+fn print(report: &Report) {
+    let path = |path: &Option<PathBuf>| {
+        path.as_ref().map_or_else(|| "none".to_string(), |path| path.display().to_string())
+    };
+
+    println!("Environment");
+    for (name, value) in &report.environment {
+        println!("  {name:<34} {}", value.as_deref().unwrap_or("(unset)"));
+    }
+    match &report.tracking_unavailable {
+        None => println!("  global tracking: on"),
+        Some(reason) => println!("  global tracking: off, {reason}"),
+    }
+
+    let persistence = &report.persistence;
+    println!("\nCalibration");
+    println!("  output key:   {}", persistence.output_key);
+    println!("  instance key: {}", persistence.instance_key);
+    println!("  state dir:    {}", path(&persistence.state_dir));
+    println!("  offset file:  {}", path(&persistence.offset_file));
+    println!("  legacy file:  {}", path(&persistence.legacy_offset_file));
+    match &persistence.saved {
+        Some(SavedOffset { file, scale, x, y, output_scale, loaded }) => {
+            println!("  saved:        scale {scale}, offset ({x}, {y}) from {}", file.display());
+            match (output_scale, loaded) {
+                (Some(_), Some(true)) => {
+                    println!("  loaded:       yes, the output is at that scale");
+                }
+                (Some(current), _) => println!(
+                    "  loaded:       no, the output is at scale {current}; hover over the eyes \
+                     to calibrate for it"
+                ),
+                (None, _) => println!(
+                    "  loaded:       only at scale {scale}; the output's scale is unknown"
+                ),
+            }
+        }
+        None => println!("  saved:        none; hover over the eyes once to calibrate"),
+    }
+
+    let wayland = &report.wayland;
+    println!("\nWayland (through {})", wayland.connection);
+    if let Some(error) = &wayland.error {
+        println!("  failed: {error}");
+        return;
+    }
+    println!("  globals:");
+    for global in &wayland.globals {
+        println!("    {} v{}", global.interface, global.version);
+    }
+    println!("  outputs (home: {}):", wayland.desired_output.as_deref().unwrap_or("any"));
+    for output in &wayland.outputs {
+        let mode = output.mode.map_or_else(|| "no mode".to_string(), |(w, h)| format!("{w}x{h}"));
+        let logical = match (output.logical_size, output.logical_position) {
+            (Some((w, h)), Some((x, y))) => format!("logical {w}x{h} at ({x}, {y})"),
+            (Some((w, h)), None) => format!("logical {w}x{h}"),
+            (None, _) => "logical size unknown".to_string(),
+        };
+        println!(
+            "  {} {}: {mode}, {}, scale {}, {logical}",
+            if output.home { '*' } else { ' ' },
+            output.name.as_deref().unwrap_or("<unnamed>"),
+            output.transform,
+            output.scale,
+        );
+        match &output.session {
+            SessionReport::Started => println!("      cursor session: started"),
+            SessionReport::Failed { error } => println!("      cursor session: failed, {error}"),
+            SessionReport::NotAttempted { reason } => {
+                println!("      cursor session: not started, {reason}");
+            }
+        }
+    }
+    match &wayland.seat {
+        Some(seat) => println!(
+            "  seat: {} ({}), pointer {}",
+            seat.name.as_deref().unwrap_or("<unnamed>"),
+            seat.capabilities.join(", "),
+            if seat.pointer { "bound" } else { "missing" },
+        ),
+        None => println!("  seat: none"),
+    }
+
+    println!("\nSamples");
+    if wayland.updates.is_empty() {
+        println!("  none within {} s", SAMPLE_TIME.as_secs());
+    }
+    let output_name = |id| {
+        let output = wayland.outputs.iter().find(|output| output.id == id);
+        output.and_then(|output| output.name.as_deref()).unwrap_or("<unnamed>")
+    };
+    for (millis, update) in &wayland.updates {
+        match update {
+            Update::Position(sample) => println!(
+                "  {millis:>5} ms  cursor at ({}, {}) on {}",
+                sample.x,
+                sample.y,
+                output_name(sample.output)
+            ),
+            Update::Left => println!("  {millis:>5} ms  cursor left every output"),
+            Update::OutputSize { width, height } => {
+                println!("  {millis:>5} ms  home output is {width}x{height} logical pixels");
+            }
+        }
+    }
+}
@@ -1,76 +1,94 @@
 use cosmic::iced::mouse;
 use cosmic::iced::widget::canvas::{self, Canvas, Geometry, Path};
-use cosmic::iced::{Color, Point, Rectangle, Size, Vector};
+use cosmic::iced::{self, Color, Rectangle};
 use cosmic::Element;
+use eyes_core::{Arrangement, Item, Paint, Palette, Point, Scene, Settings, Shape};
 
-const CANVAS_INSET: f32 = 1.0;
-const SNAP_SCALE: f32 = 2.0; // snap to half-pixels
+use crate::config::Config;
+use crate::palette;
 
-fn snap_f(value: f32) -> f32 {
-    (value * SNAP_SCALE).round() / SNAP_SCALE
-}
-
-fn snap_point(point: Point) -> Point {
-    Point::new(snap_f(point.x), snap_f(point.y))
-}
-
-fn clamp_radius_inside_circle(
-    container_center: Point,
-    container_radius: f32,
-    shape_center: Point,
-    desired_radius: f32,
-) -> f32 {
-    let dx = shape_center.x - container_center.x;
-    let dy = shape_center.y - container_center.y;
-    let max = (container_radius - (dx * dx + dy * dy).sqrt()).max(0.0);
-    desired_radius.min(max)
-}
+use std::cell::RefCell;
+use std::time::Duration;
 
+/// The eye model from `eyes-core` plus what it takes to draw it on a COSMIC canvas.
 #[derive(Debug)]
 pub struct Eyes {
-    left_pupil_offset: Vector,
-    right_pupil_offset: Vector,
-    cursor_known: bool,
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Layout {
-    eye_radius: f32,
-    pupil_radius: f32,
-    eye_spacing: f32,
+    model: eyes_core::Eyes,
+    /// Drawing options (style, iris colors); the model gets its part as `Settings`.
+    config: Config,
+    /// Ignore the configured largest eye radius (standalone window).
+    unbounded: bool,
+    layers: Layers,
 }
 
 impl Eyes {
     pub fn new() -> Self {
         Self {
-            left_pupil_offset: Vector::new(0.0, 0.0),
-            right_pupil_offset: Vector::new(0.0, 0.0),
-            cursor_known: false,
+            model: eyes_core::Eyes::new(),
+            config: Config::default(),
+            unbounded: false,
+            layers: Layers::new(),
         }
     }
 
-    pub fn tick(&mut self, cursor: Option<Point>, window_size: Size, dt: f32) {
-        self.cursor_known = cursor.is_some();
-        let (layout, left_eye_center, right_eye_center) = layout_and_centers(window_size);
+    pub fn set_config(&mut self, config: &Config) {
+        self.config = config.clone();
+        self.model.set_settings(self.settings());
+    }
 
-        // If we don't have pointer position, keep pupils centered (not looking inward).
-        let left_target = cursor.unwrap_or(left_eye_center);
-        let right_target = cursor.unwrap_or(right_eye_center);
+    /// Lets the eyes grow with the window instead of stopping at the configured largest
+    /// radius, like in a standalone window.
+    pub fn set_unbounded(&mut self, unbounded: bool) {
+        self.unbounded = unbounded;
+        self.model.set_settings(self.settings());
+    }
 
-        self.left_pupil_offset = pupil_offset(
-            left_eye_center,
-            left_target,
-            self.left_pupil_offset,
-            layout,
-            dt,
-        );
-        self.right_pupil_offset = pupil_offset(
-            right_eye_center,
-            right_target,
-            self.right_pupil_offset,
-            layout,
-            dt,
-        );
+    fn settings(&self) -> Settings {
+        let mut settings = self.config.settings();
+        if self.unbounded {
+            settings.eye_radius_range.1 = f32::INFINITY;
+        }
+        settings
+    }
+
+    /// Lets the pupils widen a little on a dark theme (if enabled in the config).
+    pub fn set_dark_theme(&mut self, dark: bool) {
+        self.model.set_dark_theme(dark);
+    }
+
+    /// Side by side on horizontal panels and stacked on vertical ones, unless the other
+    /// arrangement gives clearly bigger eyes in a window of `size`.
+    pub fn arrange(&mut self, horizontal_panel: bool, size: iced::Size) {
+        let arrangement =
+            Arrangement::for_panel(horizontal_panel, core_size(size), self.model.settings());
+        self.model.set_arrangement(arrangement);
+    }
+
+    /// Scrolling by `lines` (positive is up) steps through the expressions.
+    pub fn scroll(&mut self, lines: f32) {
+        self.model.scroll(lines);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.model.is_animating()
+    }
+
+    pub fn next_event_in(&self) -> Option<Duration> {
+        self.model.next_event_in()
+    }
+
+    /// Blinks both eyes now (e.g. when the cursor enters the applet).
+    pub fn blink(&mut self) {
+        self.model.blink();
+    }
+
+    /// Reacts to a click at `position` in a window of `window_size`.
+    pub fn poke(&mut self, position: iced::Point, window_size: iced::Size) {
+        self.model.poke(core_point(position), core_size(window_size));
+    }
+
+    pub fn tick(&mut self, cursor: Option<iced::Point>, window_size: iced::Size, dt: f32) {
+        self.model.tick(cursor.map(core_point), core_size(window_size), dt);
     }
 
     pub(crate) fn view(&self) -> Element<'_, crate::Message> {
@@ -81,67 +99,118 @@ impl Eyes {
     }
 }
 
-fn layout_for(size: Size) -> Layout {
-    let w = size.width.max(1.0);
-    let h = size.height.max(1.0);
-
-    let min_dim = w.min(h);
-    // Round to whole logical pixels so the two eyes land on stable subpixel positions.
-    let eye_spacing = (min_dim * 0.12).clamp(2.0, 10.0).round();
+fn core_point(point: iced::Point) -> Point {
+    Point::new(point.x, point.y)
+}
 
-    // Two eyes side-by-side: total width is roughly `4r + spacing`.
-    let max_r_by_width = ((w - eye_spacing) / 4.0).max(1.0);
-    let max_r_by_height = (h * 0.45).max(1.0);
+fn core_size(size: iced::Size) -> eyes_core::Size {
+    eyes_core::Size::new(size.width, size.height)
+}
 
-    let eye_radius = snap_f(max_r_by_width.min(max_r_by_height).clamp(6.0, 32.0));
-    let pupil_radius =
-        snap_f((eye_radius * 0.38).clamp(2.0, eye_radius.max(2.0) - 1.0));
+fn iced_point(point: Point) -> iced::Point {
+    iced::Point::new(point.x, point.y)
+}
 
-    Layout { eye_radius, pupil_radius, eye_spacing }
+fn iced_color(color: eyes_core::Color) -> Color {
+    Color { r: color.r, g: color.g, b: color.b, a: color.a }
 }
 
-fn layout_and_centers(size: Size) -> (Layout, Point, Point) {
-    let inset = CANVAS_INSET;
-    let w = (size.width - inset * 2.0).max(1.0);
-    let h = (size.height - inset * 2.0).max(1.0);
+fn to_path(shape: &Shape) -> Path {
+    let polyline = |builder: &mut canvas::path::Builder, points: &[Point]| {
+        for (i, point) in points.iter().enumerate() {
+            if i == 0 {
+                builder.move_to(iced_point(*point));
+            } else {
+                builder.line_to(iced_point(*point));
+            }
+        }
+    };
+    match shape {
+        Shape::Polygon(points) => Path::new(|builder| {
+            polyline(builder, points);
+            builder.close();
+        }),
+        Shape::Polyline(points) => Path::new(|builder| polyline(builder, points)),
+        Shape::Segments(segments) => Path::new(|builder| {
+            for (a, b) in segments {
+                builder.move_to(iced_point(*a));
+                builder.line_to(iced_point(*b));
+            }
+        }),
+        Shape::Circle { center, radius } => Path::circle(iced_point(*center), *radius),
+    }
+}
 
-    let usable_size = Size::new(w, h);
-    let layout = layout_for(usable_size);
+/// Draws scene `items` in order.
+fn draw_items(frame: &mut canvas::Frame<cosmic::Renderer>, items: &[Item]) {
+    for item in items {
+        let path = to_path(&item.shape);
+        match item.paint {
+            Paint::Fill(color) => frame.fill(&path, iced_color(color)),
+            Paint::Stroke(stroke) => frame.stroke(
+                &path,
+                canvas::Stroke {
+                    line_join: if stroke.round_join {
+                        canvas::LineJoin::Round
+                    } else {
+                        canvas::LineJoin::Miter
+                    },
+                    line_cap: if stroke.round_cap {
+                        canvas::LineCap::Round
+                    } else {
+                        canvas::LineCap::Butt
+                    },
+                    ..canvas::Stroke::default()
+                        .with_width(stroke.width)
+                        .with_color(iced_color(stroke.color))
+                },
+            ),
+        }
+    }
+}
 
-    let center = Point::new(inset + usable_size.width / 2.0, inset + usable_size.height / 2.0);
-    let left_eye_center =
-        Point::new(center.x - layout.eye_radius - layout.eye_spacing / 2.0, center.y);
-    let right_eye_center =
-        Point::new(center.x + layout.eye_radius + layout.eye_spacing / 2.0, center.y);
+/// Cached parts of the drawing: sclera and shading under the pupils and lids, outlines over
+/// them. Only the pupils, lids and brows are drawn from scratch every frame.
+struct Layers {
+    background: canvas::Cache<cosmic::Renderer>,
+    outline: canvas::Cache<cosmic::Renderer>,
+    /// What the cached layers were drawn with (the caches track the canvas size themselves).
+    key: RefCell<Option<LayerKey>>,
+}
 
-    (layout, left_eye_center, right_eye_center)
+#[derive(Debug, Clone, PartialEq)]
+struct LayerKey {
+    palette: Palette,
+    config: Config,
+    unbounded: bool,
+    arrangement: Arrangement,
+    cursor_known: bool,
 }
 
-fn pupil_offset(
-    eye_center: Point,
-    cursor: Point,
-    current: Vector,
-    layout: Layout,
-    dt: f32,
-) -> Vector {
-    let vx = cursor.x - eye_center.x;
-    let vy = cursor.y - eye_center.y;
-
-    let distance = (vx * vx + vy * vy).sqrt();
-    let max = (layout.eye_radius - layout.pupil_radius).max(0.0);
-
-    let target = if distance > max && distance > 0.0 {
-        let scale = max / distance;
-        Vector::new(vx * scale, vy * scale)
-    } else {
-        Vector::new(vx, vy)
-    };
+impl Layers {
+    fn new() -> Self {
+        Self {
+            background: canvas::Cache::new(),
+            outline: canvas::Cache::new(),
+            key: RefCell::new(None),
+        }
+    }
+
+    /// Drops the cached layers if they were drawn for something else than `key`.
+    fn validate(&self, key: LayerKey) {
+        let mut current = self.key.borrow_mut();
+        if current.as_ref() != Some(&key) {
+            self.background.clear();
+            self.outline.clear();
+            *current = Some(key);
+        }
+    }
+}
 
-    let t = (12.0 * dt).clamp(0.0, 1.0);
-    Vector::new(
-        current.x + (target.x - current.x) * t,
-        current.y + (target.y - current.y) * t,
-    )
+impl std::fmt::Debug for Layers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Layers").field("key", &self.key).finish_non_exhaustive()
+    }
 }
 
 impl canvas::Program<crate::Message, cosmic::Theme, cosmic::Renderer> for &Eyes {
@@ -151,90 +220,46 @@ impl canvas::Program<crate::Message, cosmic::Theme, cosmic::Renderer> for &Eyes
         &self,
         _state: &Self::State,
         renderer: &cosmic::Renderer,
-        _theme: &cosmic::Theme,
+        theme: &cosmic::Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
-        let mut frame = canvas::Frame::new(renderer, bounds.size());
-        let (layout, left_eye_center, right_eye_center) = layout_and_centers(bounds.size());
-
-        let sclera = if self.cursor_known {
-            Color::from_rgb8(250, 250, 250)
-        } else {
-            Color::from_rgb8(160, 190, 255)
-        };
-        let outline = Color::from_rgb8(24, 24, 24);
-        let shadow = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.12 };
-        let highlight = Color { r: 1.0, g: 1.0, b: 1.0, a: 0.22 };
-        let pupil = Color::from_rgb8(12, 12, 12);
-        let pupil_highlight = Color { r: 1.0, g: 1.0, b: 1.0, a: 0.30 };
-
-        let mut draw_eye = |center: Point, pupil_offset: Vector| {
-            let center = snap_point(center);
-            let pupil_center = center + pupil_offset;
-            let inner_r = (layout.eye_radius - 1.0).max(0.0);
-
-            frame.fill(
-                &Path::circle(center, layout.eye_radius),
-                sclera,
-            );
-
-            frame.stroke(
-                &Path::circle(center, layout.eye_radius - 0.5),
-                canvas::Stroke::default().with_width(1.0).with_color(outline),
-            );
-
-            // These highlights must stay inside the sclera (Canvas has no path-clip),
-            // otherwise semi-transparent pixels "bleed" outside the outline.
-            let shadow_center = snap_point(Point::new(
-                center.x + layout.eye_radius * 0.10,
-                center.y + layout.eye_radius * 0.12,
-            ));
-            let shadow_radius = clamp_radius_inside_circle(
-                center,
-                inner_r,
-                shadow_center,
-                layout.eye_radius * 0.78,
-            );
-            if shadow_radius > 0.0 {
-                frame.fill(&Path::circle(shadow_center, shadow_radius), shadow);
-            }
+        let size = bounds.size();
+
+        // Derived per frame so theme switches apply immediately.
+        let palette = palette::from_theme(theme, self.config.iris_color);
+        self.layers.validate(LayerKey {
+            palette,
+            config: self.config.clone(),
+            unbounded: self.unbounded,
+            arrangement: self.model.arrangement(),
+            cursor_known: self.model.cursor_known(),
+        });
+        let scene = Scene::new(
+            &self.model,
+            core_size(size),
+            palette,
+            self.config.style,
+            self.config.iris_striations,
+        );
+
+        let background = self.layers.background.draw(renderer, size, |frame| {
+            draw_items(frame, &scene.background());
+        });
+        let mut moving = canvas::Frame::new(renderer, size);
+        draw_items(&mut moving, &scene.moving_parts());
+        let outline = self.layers.outline.draw(renderer, size, |frame| {
+            draw_items(frame, &scene.outline());
+        });
+        let mut layers = vec![background, moving.into_geometry(), outline];
+
+        let brows = scene.brows();
+        if !brows.is_empty() {
+            let mut frame = canvas::Frame::new(renderer, size);
+            draw_items(&mut frame, &brows);
+            layers.push(frame.into_geometry());
+        }
 
-            frame.fill(
-                &Path::circle(
-                    snap_point(Point::new(
-                        center.x - layout.eye_radius * 0.18,
-                        center.y - layout.eye_radius * 0.22,
-                    )),
-                    clamp_radius_inside_circle(
-                        center,
-                        inner_r,
-                        snap_point(Point::new(
-                            center.x - layout.eye_radius * 0.18,
-                            center.y - layout.eye_radius * 0.22,
-                        )),
-                        layout.eye_radius * 0.55,
-                    ),
-                ),
-                highlight,
-            );
-
-            frame.fill(&Path::circle(pupil_center, layout.pupil_radius), pupil);
-            frame.fill(
-                &Path::circle(
-                    Point::new(
-                        pupil_center.x - layout.pupil_radius * 0.35,
-                        pupil_center.y - layout.pupil_radius * 0.35,
-                    ),
-                    (layout.pupil_radius * 0.28).max(1.0),
-                ),
-                pupil_highlight,
-            );
-        };
-
-        draw_eye(left_eye_center, self.left_pupil_offset);
-        draw_eye(right_eye_center, self.right_pupil_offset);
-
-        vec![frame.into_geometry()]
+        layers
     }
 }
@@ -4,48 +4,95 @@ use cosmic::iced_futures::{futures, stream};
 use futures::{SinkExt, StreamExt};
 
 use cosmic_client_toolkit::{
+    sctk::reexports::protocols::xdg::xdg_output::zv1::client::{
+        zxdg_output_manager_v1::ZxdgOutputManagerV1,
+        zxdg_output_v1::{self, ZxdgOutputV1},
+    },
     screencopy::{CaptureCursorSession, CaptureSource, Formats, ScreencopyHandler, ScreencopyState},
     wayland_client::{
-        Connection, Dispatch, QueueHandle, Proxy,
-        globals::{GlobalList, GlobalListContents, registry_queue_init},
+        Connection, Dispatch, EventQueue, QueueHandle, Proxy,
+        globals::{Global, GlobalList, GlobalListContents, registry_queue_init},
         protocol::{wl_output, wl_pointer, wl_registry, wl_seat},
         WEnum,
     },
 };
 
+use serde::Serialize;
+
 use std::os::{
     fd::{FromRawFd, RawFd},
     unix::net::UnixStream,
 };
+use std::time::{Duration, Instant};
 
-#[derive(Clone, Copy, Debug)]
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Identifies an output (its `wl_output` global name) for as long as it is connected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct OutputId(pub u32);
+
+/// A cursor position in logical pixels in the output layout, relative to the top-left corner
+/// of the home output (the panel's, where the applet is), as the user sees it: after rotation
+/// and scaling.
+#[derive(Clone, Copy, Debug, Serialize)]
 pub struct Sample {
+    /// The output the cursor is on.
+    pub output: OutputId,
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
 pub enum Update {
     Position(Sample),
     Left,
+    /// Logical size of the home output.
+    OutputSize { width: f32, height: f32 },
 }
 
-pub fn subscription() -> Subscription<Update> {
-    let enabled = std::env::var_os("COSMIC_EYES_DISABLE_GLOBAL_CURSOR").is_none();
-    let privileged_fd = std::env::var("X_PRIVILEGED_WAYLAND_SOCKET").ok();
+/// Why global tracking is off.
+#[derive(Clone, Copy, Debug)]
+pub enum Unavailable {
+    /// `COSMIC_EYES_DISABLE_GLOBAL_CURSOR` is set.
+    Disabled,
+    /// No `X_PRIVILEGED_WAYLAND_SOCKET`: the panel only hands it to the applets it starts.
+    NoPrivilegedSocket,
+}
 
-    if !enabled || privileged_fd.is_none() {
-        return Subscription::none();
+impl std::fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Disabled => "disabled by COSMIC_EYES_DISABLE_GLOBAL_CURSOR",
+            Self::NoPrivilegedSocket => "no privileged socket (X_PRIVILEGED_WAYLAND_SOCKET)",
+        })
+    }
+}
+
+/// The privileged socket's file descriptor number, if global tracking can run.
+pub fn privileged_socket() -> Result<String, Unavailable> {
+    if std::env::var_os("COSMIC_EYES_DISABLE_GLOBAL_CURSOR").is_some() {
+        return Err(Unavailable::Disabled);
     }
+    std::env::var("X_PRIVILEGED_WAYLAND_SOCKET").map_err(|_| Unavailable::NoPrivilegedSocket)
+}
+
+/// Global cursor updates, relative to `home_output` (a connector name), or to the panel's
+/// output (`COSMIC_PANEL_OUTPUT`) if `None`. Tracking restarts when the home output changes.
+pub fn subscription(home_output: Option<String>) -> Subscription<Update> {
+    let Ok(privileged_fd) = privileged_socket() else {
+        return Subscription::none();
+    };
+    let home_output = home_output.or_else(|| std::env::var("COSMIC_PANEL_OUTPUT").ok());
 
     Subscription::run_with_id(
-        "cosmic-ext-eyes-global-cursor",
+        ("cosmic-ext-eyes-global-cursor", home_output.clone()),
         stream::channel(32, move |mut output| async move {
             let (tx, mut rx) = futures::channel::mpsc::unbounded::<Update>();
 
-            let privileged_fd = privileged_fd.clone().unwrap();
+            let privileged_fd = privileged_fd.clone();
+            let home_output = home_output.clone();
             std::thread::spawn(move || {
-                if let Err(err) = run(privileged_fd, tx) {
+                if let Err(err) = run(privileged_fd, home_output, tx) {
                     tracing::warn!("global cursor thread failed: {err}");
                 }
             });
@@ -59,22 +106,43 @@ pub fn subscription() -> Subscription<Update> {
 
 fn run(
     privileged_fd: String,
+    home_output: Option<String>,
     tx: futures::channel::mpsc::UnboundedSender<Update>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let desired_output_name = std::env::var("COSMIC_PANEL_OUTPUT").ok();
+) -> Result<(), Error> {
+    let conn = connect_privileged(&privileged_fd)?;
+    let (watcher, event_queue) = start(&conn, home_output, tx)?;
+    watch(watcher, event_queue)
+}
 
+fn connect_privileged(privileged_fd: &str) -> Result<Connection, Error> {
     let fd = privileged_fd.parse::<RawFd>()?;
     let socket = unsafe { UnixStream::from_raw_fd(fd) };
-    let conn = Connection::from_socket(socket)?;
-    let (globals, mut event_queue) = registry_queue_init::<CursorWatcher>(&conn)?;
+    Ok(Connection::from_socket(socket)?)
+}
+
+/// Binds the globals and tries to start the cursor sessions; samples are relative to the
+/// output named `desired_output_name`.
+fn start(
+    conn: &Connection,
+    desired_output_name: Option<String>,
+    tx: futures::channel::mpsc::UnboundedSender<Update>,
+) -> Result<(CursorWatcher, EventQueue<CursorWatcher>), Error> {
+    let (globals, mut event_queue) = registry_queue_init::<CursorWatcher>(conn)?;
     let qh = event_queue.handle();
 
     let mut watcher = CursorWatcher::new(tx, desired_output_name);
     watcher.bind_initial_globals(&globals, &qh);
 
     let _ = event_queue.roundtrip(&mut watcher);
-    watcher.ensure_cursor_session(&qh);
+    watcher.ensure_cursor_sessions(&qh);
+    Ok((watcher, event_queue))
+}
 
+fn watch(
+    mut watcher: CursorWatcher,
+    mut event_queue: EventQueue<CursorWatcher>,
+) -> Result<(), Error> {
+    let qh = event_queue.handle();
     loop {
         if watcher.tx.is_closed() {
             break;
@@ -83,29 +151,226 @@ fn run(
         // If we fail to dispatch (disconnect), exit the thread.
         event_queue.blocking_dispatch(&mut watcher)?;
 
-        // If an output arrives late, we may be able to start the session now.
-        watcher.ensure_cursor_session(&qh);
+        // If the pointer arrives late, we may be able to start the sessions now.
+        watcher.ensure_cursor_sessions(&qh);
     }
 
     Ok(())
 }
 
+/// What [`probe`] found out, step by step.
+#[derive(Debug, Serialize)]
+pub struct Probe {
+    /// The socket the probe connected through.
+    pub connection: &'static str,
+    /// Why connecting or binding the globals failed; nothing else is known then.
+    pub error: Option<String>,
+    pub globals: Vec<GlobalReport>,
+    /// `COSMIC_PANEL_OUTPUT`, the applet's home output.
+    pub desired_output: Option<String>,
+    pub outputs: Vec<OutputReport>,
+    pub seat: Option<SeatReport>,
+    /// Updates received after setup, with milliseconds since the probe started.
+    pub updates: Vec<(u64, Update)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GlobalReport {
+    pub interface: String,
+    pub version: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OutputReport {
+    pub id: OutputId,
+    pub name: Option<String>,
+    /// Current mode in pixels.
+    pub mode: Option<(i32, i32)>,
+    pub transform: String,
+    /// Integer scale from `wl_output`; fractional scales only show in the logical size.
+    pub scale: i32,
+    /// From xdg-output.
+    pub logical_position: Option<(i32, i32)>,
+    /// What the samples are scaled to.
+    pub logical_size: Option<(f32, f32)>,
+    /// Device pixels per logical pixel, fractional scales included.
+    pub scale_factor: Option<f32>,
+    /// Picked by `best_output` as the applet's output, which samples are relative to.
+    pub home: bool,
+    pub session: SessionReport,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeatReport {
+    pub name: Option<String>,
+    pub capabilities: Vec<&'static str>,
+    /// Whether a pointer was bound, which the cursor session needs.
+    pub pointer: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SessionReport {
+    Started,
+    Failed { error: String },
+    NotAttempted { reason: &'static str },
+}
+
+/// Sets up global tracking the way the applet does, reports what happened at each step, then
+/// collects updates for up to `duration` or until `positions` cursor positions arrived.
+///
+/// Connects through the privileged socket if there is one (even when tracking is disabled),
+/// otherwise through `WAYLAND_DISPLAY`, where the compositor may not offer cursor capture.
+pub fn probe(positions: usize, duration: Duration) -> Probe {
+    let started = Instant::now();
+    let (connection, conn) = match std::env::var("X_PRIVILEGED_WAYLAND_SOCKET") {
+        Ok(fd) => ("X_PRIVILEGED_WAYLAND_SOCKET", connect_privileged(&fd)),
+        Err(_) => ("WAYLAND_DISPLAY", Connection::connect_to_env().map_err(Error::from)),
+    };
+    let desired_output = std::env::var("COSMIC_PANEL_OUTPUT").ok();
+    let (tx, mut rx) = futures::channel::mpsc::unbounded::<Update>();
+    let (watcher, event_queue) = match conn.and_then(|conn| start(&conn, desired_output, tx)) {
+        Ok(started) => started,
+        Err(err) => {
+            return Probe {
+                connection,
+                error: Some(err.to_string()),
+                globals: Vec::new(),
+                desired_output: std::env::var("COSMIC_PANEL_OUTPUT").ok(),
+                outputs: Vec::new(),
+                seat: None,
+                updates: Vec::new(),
+            };
+        }
+    };
+    let mut probe = watcher.report(connection);
+
+    std::thread::spawn(move || {
+        if let Err(err) = watch(watcher, event_queue) {
+            tracing::warn!("global cursor probe failed: {err}");
+        }
+    });
+    let mut seen = 0;
+    while seen < positions && started.elapsed() < duration {
+        match rx.try_next() {
+            Ok(Some(update)) => {
+                seen += usize::from(matches!(update, Update::Position(_)));
+                probe.updates.push((started.elapsed().as_millis() as u64, update));
+            }
+            // The connection is gone.
+            Ok(None) => break,
+            Err(_) => std::thread::sleep(Duration::from_millis(10)),
+        }
+    }
+    probe
+}
+
 struct OutputInfo {
     global_name: u32,
     output: wl_output::WlOutput,
+    /// Kept alive for the logical geometry events.
+    xdg_output: Option<ZxdgOutputV1>,
     name: Option<String>,
+    /// Current mode in pixels.
+    mode: Option<(i32, i32)>,
+    transform: wl_output::Transform,
+    scale: i32,
+    logical_position: Option<(i32, i32)>,
+    logical_size: Option<(i32, i32)>,
+
+    cursor_session: Option<CaptureCursorSession>,
+    cursor_session_attempted: bool,
+    cursor_session_error: Option<String>,
+}
+
+impl OutputInfo {
+    /// `None` until the current mode is known.
+    fn mapping(&self) -> Option<Mapping> {
+        let mode = self.mode?;
+        let mut mapping = Mapping { mode, transform: self.transform, logical_size: (0.0, 0.0) };
+        mapping.logical_size = match self.logical_size {
+            // xdg-output knows fractional scales.
+            Some((width, height)) if width > 0 && height > 0 => (width as f32, height as f32),
+            _ => {
+                let (width, height) = mapping.transformed_size();
+                let scale = self.scale.max(1) as f32;
+                (width / scale, height / scale)
+            }
+        };
+        Some(mapping)
+    }
+}
+
+/// How positions in an output's pixels map to its logical coordinate space.
+#[derive(Clone, Copy, Debug)]
+struct Mapping {
+    /// Current mode in pixels, before the output transform.
+    mode: (i32, i32),
+    transform: wl_output::Transform,
+    logical_size: (f32, f32),
+}
+
+impl Mapping {
+    /// Rotated 90 or 270 degrees.
+    fn swaps_axes(&self) -> bool {
+        use wl_output::Transform;
+        matches!(
+            self.transform,
+            Transform::_90 | Transform::_270 | Transform::Flipped90 | Transform::Flipped270
+        )
+    }
+
+    /// The mode in pixels as the user sees it.
+    fn transformed_size(&self) -> (f32, f32) {
+        let (width, height) = (self.mode.0 as f32, self.mode.1 as f32);
+        if self.swaps_axes() { (height, width) } else { (width, height) }
+    }
+
+    /// Device pixels per logical pixel.
+    fn scale_factor(&self) -> f32 {
+        self.transformed_size().0 / self.logical_size.0.max(1.0)
+    }
+
+    /// Maps a position in mode pixels (what cursor capture reports) to logical pixels: applies
+    /// the transform (counter-clockwise rotations, flips around the vertical axis first, as
+    /// `wl_output` defines them), then scales the transformed mode to the logical size.
+    fn to_logical(&self, x: f32, y: f32) -> (f32, f32) {
+        use wl_output::Transform;
+        let (width, height) = (self.mode.0 as f32, self.mode.1 as f32);
+        let (x, y) = match self.transform {
+            Transform::_90 => (y, width - x),
+            Transform::_180 => (width - x, height - y),
+            Transform::_270 => (height - y, x),
+            Transform::Flipped => (width - x, y),
+            Transform::Flipped90 => (y, x),
+            Transform::Flipped180 => (x, height - y),
+            Transform::Flipped270 => (height - y, width - x),
+            // `Normal`, and anything newer this doesn't know.
+            _ => (x, y),
+        };
+        let (transformed_width, transformed_height) = self.transformed_size();
+        (
+            x * self.logical_size.0 / transformed_width.max(1.0),
+            y * self.logical_size.1 / transformed_height.max(1.0),
+        )
+    }
 }
 
 struct CursorWatcher {
     screencopy: Option<ScreencopyState>,
+    xdg_output_manager: Option<ZxdgOutputManagerV1>,
     desired_output_name: Option<String>,
+    /// Every global the compositor advertised, for diagnostics.
+    globals: Vec<Global>,
 
     outputs: Vec<OutputInfo>,
     seat: Option<wl_seat::WlSeat>,
+    seat_name: Option<String>,
+    seat_capabilities: Option<wl_seat::Capability>,
     pointer: Option<wl_pointer::WlPointer>,
 
-    cursor_session: Option<CaptureCursorSession>,
-    cursor_session_attempted: bool,
+    /// Global name of the output the cursor was last seen on.
+    cursor_output: Option<u32>,
 
     tx: futures::channel::mpsc::UnboundedSender<Update>,
 }
@@ -117,12 +382,15 @@ impl CursorWatcher {
     ) -> Self {
         Self {
             screencopy: None,
+            xdg_output_manager: None,
             desired_output_name,
+            globals: Vec::new(),
             outputs: Vec::new(),
             seat: None,
+            seat_name: None,
+            seat_capabilities: None,
             pointer: None,
-            cursor_session: None,
-            cursor_session_attempted: false,
+            cursor_output: None,
             tx,
         }
     }
@@ -132,17 +400,14 @@ impl CursorWatcher {
         self.screencopy = Some(ScreencopyState::new(globals, qh));
 
         let list = globals.contents().clone_list();
-        for g in list {
+        // Bound first, so every output can get its xdg-output right away.
+        self.xdg_output_manager = list
+            .iter()
+            .find(|g| g.interface == ZxdgOutputManagerV1::interface().name)
+            .map(|g| globals.registry().bind(g.name, g.version.min(3), qh, ()));
+        for g in &list {
             match g.interface.as_str() {
-                "wl_output" => {
-                    let version = g.version.min(wl_output::WlOutput::interface().version).min(4);
-                    let output = globals.registry().bind(g.name, version, qh, g.name);
-                    self.outputs.push(OutputInfo {
-                        global_name: g.name,
-                        output,
-                        name: None,
-                    });
-                }
+                "wl_output" => self.add_output(globals.registry(), g.name, g.version, qh),
                 "wl_seat" => {
                     if self.seat.is_none() {
                         let version = g.version.min(wl_seat::WlSeat::interface().version).min(5);
@@ -153,6 +418,61 @@ impl CursorWatcher {
                 _ => {}
             }
         }
+        self.globals = list;
+    }
+
+    fn add_output(
+        &mut self,
+        registry: &wl_registry::WlRegistry,
+        global_name: u32,
+        version: u32,
+        qh: &QueueHandle<Self>,
+    ) {
+        let version = version.min(wl_output::WlOutput::interface().version).min(4);
+        let output = registry.bind(global_name, version, qh, global_name);
+        let xdg_output = self
+            .xdg_output_manager
+            .as_ref()
+            .map(|manager| manager.get_xdg_output(&output, qh, global_name));
+        self.outputs.push(OutputInfo {
+            global_name,
+            output,
+            xdg_output,
+            name: None,
+            mode: None,
+            transform: wl_output::Transform::Normal,
+            scale: 1,
+            logical_position: None,
+            logical_size: None,
+            cursor_session: None,
+            cursor_session_attempted: false,
+            cursor_session_error: None,
+        });
+    }
+
+    /// Forgets an unplugged output. If the cursor was on it, it left; if it was the home
+    /// output, samples are relative to another one from now on.
+    fn remove_output(&mut self, global_name: u32) {
+        let Some(index) = self.outputs.iter().position(|o| o.global_name == global_name) else {
+            return;
+        };
+        let home = self.best_output().map(|o| o.global_name);
+        // The cursor session is dropped with it.
+        let info = self.outputs.remove(index);
+        if let Some(xdg_output) = &info.xdg_output {
+            xdg_output.destroy();
+        }
+        if info.output.version() >= 3 {
+            info.output.release();
+        }
+
+        if self.cursor_output == Some(global_name) {
+            self.cursor_output = None;
+            let _ = self.tx.unbounded_send(Update::Left);
+        }
+        if self.best_output().map(|o| o.global_name) != home {
+            self.send_output_size();
+        }
     }
 
     fn best_output(&self) -> Option<&OutputInfo> {
@@ -168,41 +488,139 @@ impl CursorWatcher {
         self.outputs.first()
     }
 
-    fn ensure_cursor_session(&mut self, qh: &QueueHandle<Self>) {
-        if self.cursor_session_attempted {
-            return;
-        }
-        if self.cursor_session.is_some() {
-            return;
-        }
-
+    /// Starts a cursor session on every output that doesn't have one yet, so the cursor can be
+    /// followed from one to the next.
+    fn ensure_cursor_sessions(&mut self, qh: &QueueHandle<Self>) {
         let Some(pointer) = self.pointer.as_ref() else { return };
-        let Some((output, output_name)) = self.best_output().map(|o| (o.output.clone(), o.name.clone())) else { return };
         let Some(screencopy) = self.screencopy.as_ref() else { return };
-
         let capturer = screencopy.capturer();
-        let source = CaptureSource::Output(output);
-
-        match capturer.create_cursor_session(
-            &source,
-            pointer,
-            qh,
-            cosmic_client_toolkit::screencopy::ScreencopyCursorSessionData::default(),
-        ) {
-            Ok(session) => {
-                self.cursor_session = Some(session);
-                self.cursor_session_attempted = true;
-                tracing::info!(
-                    output = output_name.as_deref().unwrap_or("<unknown>"),
-                    "global cursor session started"
-                );
+
+        let mut started = false;
+        for info in self.outputs.iter_mut().filter(|o| !o.cursor_session_attempted) {
+            info.cursor_session_attempted = true;
+            let output_name = info.name.as_deref().unwrap_or("<unknown>");
+            match capturer.create_cursor_session(
+                &CaptureSource::Output(info.output.clone()),
+                pointer,
+                qh,
+                cosmic_client_toolkit::screencopy::ScreencopyCursorSessionData::default(),
+            ) {
+                Ok(session) => {
+                    info.cursor_session = Some(session);
+                    started = true;
+                    tracing::info!(output = output_name, "global cursor session started");
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        output = output_name,
+                        "failed to start global cursor session: {err}"
+                    );
+                    info.cursor_session_error = Some(err.to_string());
+                }
             }
-            Err(err) => {
-                self.cursor_session_attempted = true;
-                tracing::warn!("failed to start global cursor session: {err}");
+        }
+        if started {
+            self.send_output_size();
+        }
+    }
+
+    /// The output a cursor session reports for.
+    fn session_output(&self, session: &CaptureCursorSession) -> Option<&OutputInfo> {
+        self.outputs.iter().find(|o| o.cursor_session.as_ref() == Some(session))
+    }
+
+    /// Places a position on `output` (in its mode pixels) in the output layout, relative to
+    /// the home output. `None` if the layout doesn't tell where `output` is.
+    fn to_home(&self, output: &OutputInfo, x: f32, y: f32) -> Option<Sample> {
+        let (x, y) = output.mapping()?.to_logical(x, y);
+        let home = self.best_output()?;
+        let (dx, dy) = if home.global_name == output.global_name {
+            (0, 0)
+        } else {
+            // Other outputs can only be placed with xdg-output's logical positions.
+            let (home_x, home_y) = home.logical_position?;
+            let (output_x, output_y) = output.logical_position?;
+            (output_x - home_x, output_y - home_y)
+        };
+        Some(Sample { output: OutputId(output.global_name), x: x + dx as f32, y: y + dy as f32 })
+    }
+
+    fn report(&self, connection: &'static str) -> Probe {
+        let home = self.best_output().map(|o| o.global_name);
+        let session = |o: &OutputInfo| {
+            if o.cursor_session.is_some() {
+                SessionReport::Started
+            } else if let Some(error) = &o.cursor_session_error {
+                SessionReport::Failed { error: error.clone() }
+            } else {
+                // The same order `ensure_cursor_sessions` checks in.
+                let reason = if self.seat.is_none() {
+                    "no wl_seat"
+                } else if self.pointer.is_none() {
+                    "the seat has no pointer"
+                } else {
+                    "not started yet"
+                };
+                SessionReport::NotAttempted { reason }
             }
+        };
+
+        Probe {
+            connection,
+            error: None,
+            globals: self
+                .globals
+                .iter()
+                .map(|g| GlobalReport { interface: g.interface.clone(), version: g.version })
+                .collect(),
+            desired_output: self.desired_output_name.clone(),
+            outputs: self
+                .outputs
+                .iter()
+                .map(|o| OutputReport {
+                    id: OutputId(o.global_name),
+                    name: o.name.clone(),
+                    mode: o.mode,
+                    transform: format!("{:?}", o.transform),
+                    scale: o.scale,
+                    logical_position: o.logical_position,
+                    logical_size: o.mapping().map(|mapping| mapping.logical_size),
+                    scale_factor: o.mapping().map(|mapping| mapping.scale_factor()),
+                    home: home == Some(o.global_name),
+                    session: session(o),
+                })
+                .collect(),
+            seat: self.seat.as_ref().map(|_| SeatReport {
+                name: self.seat_name.clone(),
+                capabilities: [
+                    (wl_seat::Capability::Pointer, "pointer"),
+                    (wl_seat::Capability::Keyboard, "keyboard"),
+                    (wl_seat::Capability::Touch, "touch"),
+                ]
+                .into_iter()
+                .filter(|(capability, _)| {
+                    self.seat_capabilities.is_some_and(|all| all.contains(*capability))
+                })
+                .map(|(_, name)| name)
+                .collect(),
+                pointer: self.pointer.is_some(),
+            }),
+            updates: Vec::new(),
         }
     }
+
+    fn is_home(&self, global_name: u32) -> bool {
+        self.best_output().is_some_and(|o| o.global_name == global_name)
+    }
+
+    fn send_output_size(&self) {
+        let Some(Mapping { logical_size: (width, height), .. }) =
+            self.best_output().and_then(OutputInfo::mapping)
+        else {
+            return;
+        };
+        let _ = self.tx.unbounded_send(Update::OutputSize { width, height });
+    }
 }
 
 impl ScreencopyHandler for CursorWatcher {
@@ -251,36 +669,56 @@ impl ScreencopyHandler for CursorWatcher {
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _cursor_session: &CaptureCursorSession,
+        cursor_session: &CaptureCursorSession,
         x: i32,
         y: i32,
     ) {
-        let _ = self.tx.unbounded_send(Update::Position(Sample {
-            x: x as f32,
-            y: y as f32,
-        }));
+        let Some(output) = self.session_output(cursor_session) else { return };
+        // Without the output's mode and place in the layout there is no telling where that is.
+        let Some(sample) = self.to_home(output, x as f32, y as f32) else { return };
+        self.cursor_output = Some(sample.output.0);
+        let _ = self.tx.unbounded_send(Update::Position(sample));
     }
 
     fn cursor_leave(
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _cursor_session: &CaptureCursorSession,
+        cursor_session: &CaptureCursorSession,
     ) {
-        let _ = self.tx.unbounded_send(Update::Left);
+        let output = self.session_output(cursor_session).map(|o| o.global_name);
+        // Moving to another output, the cursor may enter there before it leaves here.
+        if output.is_some() && output == self.cursor_output {
+            self.cursor_output = None;
+            let _ = self.tx.unbounded_send(Update::Left);
+        }
     }
 }
 
 impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for CursorWatcher {
     fn event(
-        _state: &mut Self,
-        _proxy: &wl_registry::WlRegistry,
-        _event: wl_registry::Event,
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
         _data: &GlobalListContents,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
+        qh: &QueueHandle<Self>,
     ) {
-        // For now we only bind globals during initial setup.
+        match event {
+            wl_registry::Event::Global { name, interface, version } => {
+                // A plugged in output; the cursor can be followed onto it too.
+                if interface == wl_output::WlOutput::interface().name {
+                    state.add_output(registry, name, version, qh);
+                    state.ensure_cursor_sessions(qh);
+                }
+                state.globals.push(Global { name, interface, version });
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                state.globals.retain(|g| g.name != name);
+                state.remove_output(name);
+            }
+            _ => {}
+        }
     }
 }
 
@@ -293,6 +731,7 @@ impl Dispatch<wl_output::WlOutput, u32> for CursorWatcher {
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
     ) {
+        let home = state.is_home(*global_name);
         let info = state
             .outputs
             .iter_mut()
@@ -304,6 +743,59 @@ impl Dispatch<wl_output::WlOutput, u32> for CursorWatcher {
             wl_output::Event::Name { name } => {
                 info.name = Some(name);
             }
+            wl_output::Event::Geometry { transform: WEnum::Value(transform), .. } => {
+                info.transform = transform;
+            }
+            wl_output::Event::Scale { factor } => {
+                info.scale = factor;
+            }
+            wl_output::Event::Mode { flags: WEnum::Value(flags), width, height, .. }
+                if flags.contains(wl_output::Mode::Current) =>
+            {
+                info.mode = Some((width, height));
+            }
+            // The end of a batch of changes, also for xdg-output from version 3.
+            wl_output::Event::Done if home => state.send_output_size(),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZxdgOutputManagerV1, ()> for CursorWatcher {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZxdgOutputManagerV1,
+        _event: <ZxdgOutputManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZxdgOutputV1, u32> for CursorWatcher {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZxdgOutputV1,
+        event: zxdg_output_v1::Event,
+        global_name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let home = state.is_home(*global_name);
+        let Some(info) = state.outputs.iter_mut().find(|o| o.global_name == *global_name) else {
+            return;
+        };
+
+        match event {
+            zxdg_output_v1::Event::LogicalPosition { x, y } => {
+                info.logical_position = Some((x, y));
+            }
+            zxdg_output_v1::Event::LogicalSize { width, height } => {
+                info.logical_size = Some((width, height));
+            }
+            // Only sent before version 3; `wl_output::Event::Done` ends the batch after that.
+            zxdg_output_v1::Event::Done if home => state.send_output_size(),
             _ => {}
         }
     }
@@ -319,7 +811,13 @@ impl Dispatch<wl_seat::WlSeat, ()> for CursorWatcher {
         qh: &QueueHandle<Self>,
     ) {
         match event {
+            wl_seat::Event::Name { name } => {
+                state.seat_name = Some(name);
+            }
             wl_seat::Event::Capabilities { capabilities } => {
+                if let WEnum::Value(capabilities) = capabilities {
+                    state.seat_capabilities = Some(capabilities);
+                }
                 if state.pointer.is_none() {
                     if let WEnum::Value(capabilities) = capabilities {
                         if capabilities.contains(wl_seat::Capability::Pointer) {
@@ -346,3 +844,42 @@ impl Dispatch<wl_pointer::WlPointer, ()> for CursorWatcher {
 }
 
 cosmic_client_toolkit::delegate_screencopy!(CursorWatcher);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use wl_output::Transform;
+
+    #[test]
+    fn fractional_scales_come_from_the_logical_size() {
+        // 150 %: xdg-output reports 2560x1440 for a 3840x2160 mode.
+        let mapping = Mapping {
+            mode: (3840, 2160),
+            transform: Transform::Normal,
+            logical_size: (2560.0, 1440.0),
+        };
+        assert_eq!(mapping.to_logical(3840.0, 1080.0), (2560.0, 720.0));
+    }
+
+    #[test]
+    fn transforms_move_the_first_pixel_to_the_right_corner() {
+        // Where the mode's top-left pixel ends up once the output is rotated and flipped.
+        let cases = [
+            (Transform::Normal, (0.0, 0.0)),
+            (Transform::_90, (0.0, 3840.0)),
+            (Transform::_180, (3840.0, 2160.0)),
+            (Transform::_270, (2160.0, 0.0)),
+            (Transform::Flipped, (3840.0, 0.0)),
+            (Transform::Flipped90, (0.0, 0.0)),
+            (Transform::Flipped180, (0.0, 2160.0)),
+            (Transform::Flipped270, (2160.0, 3840.0)),
+        ];
+        for (transform, expected) in cases {
+            let mut mapping = Mapping { mode: (3840, 2160), transform, logical_size: (0.0, 0.0) };
+            // Scale 1: portrait outputs are 2160 logical pixels wide.
+            mapping.logical_size = mapping.transformed_size();
+            assert_eq!(mapping.to_logical(0.0, 0.0), expected, "{transform:?}");
+        }
+    }
+}
@@ -1,18 +1,48 @@
+mod cli;
+mod config;
+mod diagnose;
 mod global_cursor;
 mod eyes;
+mod offset;
+mod overlay;
+mod palette;
+mod panel;
 mod persist;
+mod popup;
 
 use cosmic::app::{Core, Task};
-use cosmic::iced::event::{self, Event};
+use cosmic::applet::cosmic_panel_config::{CosmicPanelConfig, PanelAnchor};
+use cosmic::cctk::wayland_client::protocol::wl_output::WlOutput;
+use cosmic::cosmic_config;
+use cosmic::iced::event::{self, wayland, Event};
 use cosmic::iced::mouse;
-use cosmic::iced::{Point, Size, Subscription, Vector};
+use cosmic::iced::platform_specific::shell::commands::layer_surface::{
+    destroy_layer_surface, get_layer_surface,
+};
+use cosmic::iced::platform_specific::shell::commands::popup::{destroy_popup, get_popup};
+use cosmic::iced::window::Id;
+use cosmic::iced::{Limits, Point, Size, Subscription, Vector};
 use cosmic::Element;
 
 use std::time::{Duration, Instant};
 
 const APP_ID: &str = "com.xinia.CosmicAppletEyes";
+/// Touchpads scroll in pixels; this many count as one wheel notch.
+const PIXELS_PER_LINE: f32 = 40.0;
+/// Shortest sleep between ticks while the eyes rest.
+const MIN_WAKE_UP: Duration = Duration::from_millis(10);
+/// First size of the standalone window, until it remembers its own.
+const DEFAULT_WINDOW_SIZE: Size = Size::new(240.0, 120.0);
 
 fn main() -> cosmic::iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "calibration") {
+        std::process::exit(cli::calibration(&args[1..]));
+    }
+    if args.iter().any(|arg| arg == "--diagnose") {
+        std::process::exit(diagnose::run(args.iter().any(|arg| arg == "--json")));
+    }
+
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
@@ -20,26 +50,112 @@ fn main() -> cosmic::iced::Result {
         )
         .init();
 
-    cosmic::applet::run::<EyesApplet>(())
+    let mode = args
+        .iter()
+        .find_map(|arg| match arg.as_str() {
+            "--window" => Some(Mode::Window),
+            "--overlay" => Some(Mode::Overlay),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    match mode {
+        Mode::Applet => cosmic::applet::run::<EyesApplet>(mode),
+        Mode::Window => {
+            let size = persist::load_window_size().unwrap_or(DEFAULT_WINDOW_SIZE);
+            // Server-side decorations: the canvas gets the whole surface, so window-local
+            // cursor positions are canvas positions, as in the panel.
+            let settings = cosmic::app::Settings::default()
+                .size(size)
+                .size_limits(Limits::NONE.min_width(24.0).min_height(24.0))
+                .client_decorations(false);
+            cosmic::app::run::<EyesApplet>(settings, mode)
+        }
+        // The layer surface is opened once the output it goes on is known.
+        Mode::Overlay => {
+            let settings =
+                cosmic::app::Settings::default().no_main_window(true).transparent(true);
+            cosmic::app::run::<EyesApplet>(settings, mode)
+        }
+    }
+}
+
+/// Where the eyes live.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Mode {
+    /// In a COSMIC panel or dock.
+    #[default]
+    Applet,
+    /// In a resizable window of their own (`--window`), like classic xeyes.
+    Window,
+    /// On a click-through layer-shell surface on the desktop (`--overlay`), following the
+    /// cursor through global tracking only.
+    Overlay,
 }
 
 #[derive(Debug, Clone)]
 enum Message {
-    CursorMoved(Point),
-    CursorLeft,
+    CursorMoved(Id, Point),
+    CursorLeft(Id),
+    ButtonPressed(Id, mouse::Button),
+    WheelScrolled(Id, mouse::ScrollDelta),
     GlobalCursor(global_cursor::Update),
+    /// An output was announced or renamed, with its connector name.
+    OutputChanged(WlOutput, Option<String>),
+    OutputRemoved(WlOutput),
     Tick(Instant),
+    ConfigChanged(config::Config),
+    /// The configuration of the panel the applet is in changed.
+    PanelConfigChanged(CosmicPanelConfig),
+    TogglePopup,
+    PopupClosed(Id),
+    /// The standalone window is closing.
+    WindowClosed,
+    PreviewCursor(Option<Point>),
+    SetEyeSize(f32),
+    SetPupilRatio(f32),
+    SetFollowSpeed(f32),
+    SetGlobalTracking(bool),
+    SetIris(bool),
+    SetEyeball(bool),
+    SetStyle(usize),
+    SetShape(usize),
+    /// Persist and apply the popup's draft settings.
+    CommitSettings,
 }
 
 struct EyesApplet {
     core: Core,
+    mode: Mode,
     last_tick: Instant,
+    /// Tick every frame; otherwise only wake up for the next scheduled event.
+    animating: bool,
     hover_cursor: Option<Point>,
     global_cursor: Option<Timed<global_cursor::Sample>>,
-    global_to_local_offset: Option<Vector>,
-    offset_dirty: bool,
+    /// Logical size of the output the global cursor is tracked on.
+    output_size: Option<Size>,
+    global_to_local_offset: offset::Offset,
+    /// Panel edge and scale factor the applet was calibrated at.
+    placement: Option<(PanelAnchor, f32)>,
+    /// Configuration of the panel the applet is in, to work out where it sits.
+    panel_config: Option<CosmicPanelConfig>,
     window_size: Size,
+    /// The standalone window was resized since its size was last saved.
+    window_size_dirty: bool,
     eyes: eyes::Eyes,
+    config: config::Config,
+    config_handler: Option<cosmic_config::Config>,
+    popup: Option<Id>,
+    /// The layer surface in `Mode::Overlay`.
+    overlay: Option<Id>,
+    /// Connected outputs by connector name, to put the overlay on.
+    outputs: Vec<(WlOutput, String)>,
+    /// The output the overlay is on, which global tracking measures against.
+    overlay_output: Option<String>,
+    /// Settings being edited in the popup, shown by `preview`.
+    draft: config::Config,
+    preview: eyes::Eyes,
+    preview_cursor: Option<Point>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -52,20 +168,34 @@ impl Default for EyesApplet {
     fn default() -> Self {
         Self {
             core: Core::default(),
+            mode: Mode::Applet,
             last_tick: Instant::now(),
+            animating: true,
             hover_cursor: None,
             global_cursor: None,
-            global_to_local_offset: None,
-            offset_dirty: false,
+            output_size: None,
+            global_to_local_offset: offset::Offset::default(),
+            placement: None,
+            panel_config: None,
             window_size: Size::new(1.0, 1.0),
+            window_size_dirty: false,
             eyes: eyes::Eyes::new(),
+            config: config::Config::default(),
+            config_handler: None,
+            popup: None,
+            overlay: None,
+            outputs: Vec::new(),
+            overlay_output: None,
+            draft: config::Config::default(),
+            preview: eyes::Eyes::new(),
+            preview_cursor: None,
         }
     }
 }
 
 impl cosmic::Application for EyesApplet {
     type Executor = cosmic::SingleThreadExecutor;
-    type Flags = ();
+    type Flags = Mode;
     type Message = Message;
 
     const APP_ID: &'static str = APP_ID;
@@ -78,139 +208,518 @@ impl cosmic::Application for EyesApplet {
         &mut self.core
     }
 
-    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Message>) {
+    fn init(mut core: Core, mode: Self::Flags) -> (Self, Task<Message>) {
+        let config_handler = config::Config::handler(APP_ID);
+        let config = config_handler
+            .as_ref()
+            .map(config::Config::load)
+            .unwrap_or_default();
+        if mode == Mode::Window {
+            core.window.show_headerbar = false;
+        }
         let mut app = Self {
             core,
+            mode,
+            config_handler,
             ..Self::default()
         };
-        let scale = app.core.scale_factor().max(0.1);
-        app.global_to_local_offset = persist::load_offset(scale);
+        // Windows and overlays can be as big as the user likes; the eyes should fill them.
+        app.eyes.set_unbounded(mode != Mode::Applet);
+        app.apply_config(config);
+        // The offset is kept per panel output and applet instance; a window moves around, so
+        // it only calibrates while hovered.
+        if mode == Mode::Applet {
+            let scale = app.core.scale_factor().max(0.1);
+            app.global_to_local_offset = offset::Offset::saved(persist::load_offset(scale));
+            app.placement = Some((app.core.applet.anchor, app.core.scale_factor()));
+            app.panel_config = panel::config(&app.core.applet.panel_type);
+        }
+        if mode == Mode::Overlay {
+            let (width, height) = app.config.overlay_size();
+            app.window_size = Size::new(width as f32, height as f32);
+        }
         (app, Task::none())
     }
 
-    fn on_window_resize(&mut self, _id: cosmic::iced::window::Id, width: f32, height: f32) {
+    fn on_window_resize(&mut self, id: Id, width: f32, height: f32) {
+        if self.popup == Some(id) {
+            return;
+        }
         self.window_size = Size::new(width.max(1.0), height.max(1.0));
+        self.window_size_dirty = self.mode == Mode::Window;
+        self.estimate_offset_from_panel();
     }
 
     fn subscription(&self) -> Subscription<Message> {
         Subscription::batch(vec![
-            cosmic::iced::event::listen_with(|event, status, _id| {
+            cosmic::iced::event::listen_with(|event, status, id| {
                 if let event::Status::Captured = status {
                     return None;
                 }
 
                 match event {
                     Event::Mouse(mouse::Event::CursorMoved { position }) => {
-                        Some(Message::CursorMoved(position))
+                        Some(Message::CursorMoved(id, position))
                     }
-                    Event::Mouse(mouse::Event::CursorLeft) => Some(Message::CursorLeft),
+                    Event::Mouse(mouse::Event::CursorLeft) => Some(Message::CursorLeft(id)),
+                    Event::Mouse(mouse::Event::ButtonPressed(button)) => {
+                        Some(Message::ButtonPressed(id, button))
+                    }
+                    Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                        Some(Message::WheelScrolled(id, delta))
+                    }
+                    Event::PlatformSpecific(event::PlatformSpecific::Wayland(
+                        wayland::Event::Output(event, output),
+                    )) => match event {
+                        wayland::OutputEvent::Created(info) => {
+                            Some(Message::OutputChanged(output, info.and_then(|info| info.name)))
+                        }
+                        wayland::OutputEvent::InfoUpdate(info) => {
+                            Some(Message::OutputChanged(output, info.name))
+                        }
+                        wayland::OutputEvent::Removed => Some(Message::OutputRemoved(output)),
+                    },
                     _ => None,
                 }
             }),
-            global_cursor::subscription().map(Message::GlobalCursor),
-            cosmic::iced::time::every(Duration::from_millis(1000 / 60)).map(Message::Tick),
+            match self.mode {
+                // The overlay never gets pointer events of its own, and is measured against
+                // the output it is on.
+                Mode::Overlay => match &self.overlay_output {
+                    Some(output) => global_cursor::subscription(Some(output.clone()))
+                        .map(Message::GlobalCursor),
+                    None => Subscription::none(),
+                },
+                Mode::Applet | Mode::Window if self.config.global_tracking => {
+                    global_cursor::subscription(None).map(Message::GlobalCursor)
+                }
+                Mode::Applet | Mode::Window => Subscription::none(),
+            },
+            self.tick_subscription(),
+            self.core
+                .watch_config::<config::Config>(APP_ID)
+                .map(|update| Message::ConfigChanged(update.config)),
+            if self.mode == Mode::Applet {
+                panel::watch_config(&self.core.applet.panel_type).map(Message::PanelConfigChanged)
+            } else {
+                Subscription::none()
+            },
         ])
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
+        // Anything but a tick may change what the eyes should do; let them catch up.
+        if !matches!(message, Message::Tick(_)) {
+            self.animating = true;
+        }
+        if self.mode == Mode::Applet {
+            self.check_placement();
+        }
+
         match message {
-            Message::CursorMoved(position) => {
+            // The popup tracks its own cursor for the preview and has its own controls.
+            Message::CursorMoved(id, _)
+            | Message::CursorLeft(id)
+            | Message::ButtonPressed(id, _)
+            | Message::WheelScrolled(id, _)
+                if self.popup == Some(id) => {}
+            Message::CursorMoved(_, position) => {
                 let now = Instant::now();
+                if self.hover_cursor.is_none() {
+                    self.eyes.blink();
+                }
                 self.hover_cursor = Some(position);
-                self.maybe_calibrate(now, position);
+                let estimate = self.global_to_local_offset.push_local(now, position);
+                log_estimate(estimate);
             }
-            Message::CursorLeft => {
+            Message::CursorLeft(_) => {
                 self.hover_cursor = None;
-                if self.offset_dirty {
-                    if let Some(offset) = self.global_to_local_offset {
+                // The window may be moved before the cursor comes back.
+                if self.mode == Mode::Window {
+                    self.reset_calibration();
+                }
+                if let Some(offset) = self.global_to_local_offset.take_unsaved() {
+                    if self.mode == Mode::Applet {
                         let scale = self.core.scale_factor().max(0.1);
                         let _ = persist::save_offset(scale, offset);
                     }
-                    self.offset_dirty = false;
+                }
+                self.save_window_size();
+            }
+            Message::ButtonPressed(_, button) => {
+                if let (mouse::Button::Left, Some(position)) = (button, self.hover_cursor) {
+                    self.eyes.poke(position, self.window_size);
                 }
             }
+            Message::WheelScrolled(_, delta) => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+                };
+                self.eyes.scroll(lines);
+            }
             Message::GlobalCursor(sample) => {
                 match sample {
                     global_cursor::Update::Position(sample) => {
-                        self.global_cursor = Some(Timed { value: sample, at: Instant::now() });
+                        let now = Instant::now();
+                        self.global_cursor = Some(Timed { value: sample, at: now });
+                        let global = Point::new(sample.x, sample.y);
+                        let estimate = self.global_to_local_offset.push_global(now, global);
+                        log_estimate(estimate);
                     }
                     global_cursor::Update::Left => {
                         self.global_cursor = None;
                     }
+                    global_cursor::Update::OutputSize { width, height } => {
+                        let size = Size::new(width, height);
+                        // On a resized output the applet may sit elsewhere, e.g. at its end.
+                        if self.output_size.is_some_and(|old| old != size) {
+                            self.reset_calibration();
+                        }
+                        self.output_size = Some(size);
+                        self.estimate_offset_from_panel();
+                    }
                 }
             }
+            Message::OutputChanged(output, name) => {
+                // Unnamed outputs can't be matched with global tracking's; wait for the name.
+                let Some(name) = name else { return Task::none() };
+                match self.outputs.iter_mut().find(|(known, _)| *known == output) {
+                    Some(entry) => entry.1 = name,
+                    None => self.outputs.push((output, name)),
+                }
+                return self.place_overlay();
+            }
+            Message::OutputRemoved(output) => {
+                self.outputs.retain(|(known, _)| *known != output);
+                return self.place_overlay();
+            }
             Message::Tick(now) => {
                 let dt = (now - self.last_tick).as_secs_f32();
                 self.last_tick = now;
 
-                let cursor = if let Some(local) = self.hover_cursor {
-                    Some(local)
-                } else if let (Some(Timed { value: sample, .. }), Some(offset)) =
-                    (self.global_cursor, self.global_to_local_offset)
-                {
-                    let scale = self.core.scale_factor().max(0.1);
-                    let global = Point::new(sample.x / scale, sample.y / scale);
-                    Some(Point::new(global.x - offset.x, global.y - offset.y))
-                } else {
-                    None
-                };
+                let cursor = self.cursor();
 
+                // The panel anchor can change at runtime (e.g. the panel is moved to another edge).
+                let horizontal = match self.mode {
+                    Mode::Applet => self.core.applet.is_horizontal(),
+                    Mode::Window | Mode::Overlay => {
+                        self.window_size.width >= self.window_size.height
+                    }
+                };
+                self.eyes.arrange(horizontal, self.window_size);
+                self.eyes.set_dark_theme(cosmic::theme::active().cosmic().is_dark);
                 self.eyes.tick(cursor, self.window_size, dt);
+                if self.popup.is_some() {
+                    self.preview.set_dark_theme(cosmic::theme::active().cosmic().is_dark);
+                    self.preview.tick(self.preview_cursor, popup::PREVIEW_SIZE, dt);
+                }
+                // The popup is short-lived; keep its preview simple and always running.
+                self.animating = self.eyes.is_animating() || self.popup.is_some();
+            }
+            Message::ConfigChanged(config) => {
+                if config != self.config {
+                    let moved = overlay::surface_changed(&self.config, &config);
+                    self.apply_config(config);
+                    if self.mode == Mode::Overlay && moved {
+                        return self.open_overlay();
+                    }
+                }
+            }
+            Message::PanelConfigChanged(config) => {
+                let before = self.panel_origin();
+                self.panel_config = Some(config);
+                // Reordered or resized applets move this one; what was measured is stale.
+                if self.panel_origin() != before {
+                    self.reset_calibration();
+                }
+                self.estimate_offset_from_panel();
+            }
+            Message::TogglePopup => {
+                return if let Some(id) = self.popup.take() {
+                    destroy_popup(id)
+                } else {
+                    let Some(parent) = self.core.main_window_id() else {
+                        return Task::none();
+                    };
+                    let id = Id::unique();
+                    self.popup = Some(id);
+                    self.set_draft(self.config.clone());
+                    self.preview_cursor = None;
+
+                    let mut popup_settings =
+                        self.core.applet.get_popup_settings(parent, id, None, None, None);
+                    popup_settings.positioner.size_limits = Limits::NONE
+                        .min_width(300.0)
+                        .max_width(372.0)
+                        .min_height(200.0)
+                        .max_height(1080.0);
+                    get_popup(popup_settings)
+                };
+            }
+            Message::PopupClosed(id) => {
+                if self.popup == Some(id) {
+                    self.popup = None;
+                }
+            }
+            Message::WindowClosed => self.save_window_size(),
+            Message::PreviewCursor(position) => {
+                if position.is_some() && self.preview_cursor.is_none() {
+                    self.preview.blink();
+                }
+                self.preview_cursor = position;
+            }
+            Message::SetEyeSize(radius) => {
+                let mut draft = self.draft.clone();
+                draft.max_eye_radius = radius;
+                draft.min_eye_radius = draft.min_eye_radius.min(radius);
+                self.set_draft(draft);
+            }
+            Message::SetPupilRatio(ratio) => {
+                let mut draft = self.draft.clone();
+                draft.pupil_ratio = ratio;
+                self.set_draft(draft);
+            }
+            Message::SetFollowSpeed(speed) => {
+                let mut draft = self.draft.clone();
+                draft.follow_speed = speed;
+                self.set_draft(draft);
+            }
+            Message::SetGlobalTracking(enabled) => {
+                let mut draft = self.draft.clone();
+                draft.global_tracking = enabled;
+                self.set_draft(draft);
+                self.commit_draft();
+            }
+            Message::SetIris(enabled) => {
+                let mut draft = self.draft.clone();
+                draft.iris = enabled;
+                self.set_draft(draft);
+                self.commit_draft();
+            }
+            Message::SetEyeball(enabled) => {
+                let mut draft = self.draft.clone();
+                draft.gaze = if enabled { config::Gaze::Eyeball } else { config::Gaze::Flat };
+                self.set_draft(draft);
+                self.commit_draft();
             }
+            Message::SetStyle(index) => {
+                if let Some(style) = popup::style_at(index) {
+                    let mut draft = self.draft.clone();
+                    draft.style = style;
+                    self.set_draft(draft);
+                    self.commit_draft();
+                }
+            }
+            Message::SetShape(index) => {
+                if let Some(shape) = popup::shape_at(index) {
+                    let mut draft = self.draft.clone();
+                    draft.shape = shape;
+                    self.set_draft(draft);
+                    self.commit_draft();
+                }
+            }
+            Message::CommitSettings => self.commit_draft(),
         }
 
         Task::none()
     }
 
     fn view(&self) -> Element<'_, Message> {
-        self.eyes.view()
+        // Left clicks poke the eyes (see `Message::ButtonPressed`).
+        cosmic::iced::widget::mouse_area(self.eyes.view())
+            .on_right_press(Message::TogglePopup)
+            .into()
+    }
+
+    fn view_window(&self, id: Id) -> Element<'_, Message> {
+        if self.popup == Some(id) {
+            popup::view(&self.core, &self.draft, &self.preview)
+        } else {
+            self.view()
+        }
     }
 
-    fn view_window(&self, _id: cosmic::iced::window::Id) -> Element<'_, Message> {
-        self.view()
+    fn on_close_requested(&self, id: Id) -> Option<Message> {
+        if self.mode == Mode::Window && self.popup != Some(id) {
+            Some(Message::WindowClosed)
+        } else {
+            Some(Message::PopupClosed(id))
+        }
     }
 
     fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
-        Some(cosmic::applet::style())
+        match self.mode {
+            // Transparent around the eyes.
+            Mode::Applet | Mode::Overlay => Some(cosmic::applet::style()),
+            Mode::Window => None,
+        }
     }
 }
 
 impl EyesApplet {
-    fn maybe_calibrate(&mut self, now: Instant, local: Point) {
-        const MAX_SKEW: Duration = Duration::from_millis(80);
-        let Some(Timed { value: global, at: global_at }) = self.global_cursor else {
-            return;
+    /// Ticks every frame while animating. At rest there is nothing to poll: sleep until the
+    /// next blink or idle change, or until a message sets `animating` again.
+    fn tick_subscription(&self) -> Subscription<Message> {
+        if self.animating {
+            if self.config.sync_to_frames {
+                cosmic::iced::window::frames().map(Message::Tick)
+            } else {
+                cosmic::iced::time::every(Duration::from_secs(1) / self.config.tick_rate())
+                    .map(Message::Tick)
+            }
+        } else if let Some(wait) = self.eyes.next_event_in() {
+            cosmic::iced::time::every(wait.max(MIN_WAKE_UP)).map(Message::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// Where the cursor is relative to the eyes' surface, if known.
+    fn cursor(&self) -> Option<Point> {
+        if let Some(local) = self.hover_cursor {
+            return Some(local);
+        }
+        let Timed { value: global, .. } = self.global_cursor?;
+        let offset = match self.mode {
+            Mode::Applet | Mode::Window => self.global_to_local_offset.get()?,
+            // The compositor places the overlay exactly where its settings say.
+            Mode::Overlay => {
+                let origin = overlay::origin(&self.config, self.output_size?);
+                Vector::new(origin.x, origin.y)
+            }
         };
-        if now.saturating_duration_since(global_at) > MAX_SKEW {
+        Some(Point::new(global.x - offset.x, global.y - offset.y))
+    }
+
+    /// The output the overlay belongs on: the configured one if it is connected, else the
+    /// first one.
+    fn overlay_target(&self) -> Option<&(WlOutput, String)> {
+        let configured = self.config.overlay_output.as_deref();
+        self.outputs
+            .iter()
+            .find(|(_, name)| Some(name.as_str()) == configured)
+            .or_else(|| self.outputs.first())
+    }
+
+    /// Reopens the overlay if it isn't on the output it belongs on (any more).
+    fn place_overlay(&mut self) -> Task<Message> {
+        let target = self.overlay_target().map(|(_, name)| name.as_str());
+        if self.mode != Mode::Overlay || target == self.overlay_output.as_deref() {
+            return Task::none();
+        }
+        self.open_overlay()
+    }
+
+    /// Opens the overlay's layer surface with the current settings on the output it belongs
+    /// on, replacing any open one; without outputs it is only closed.
+    fn open_overlay(&mut self) -> Task<Message> {
+        let target = self.overlay_target().cloned();
+        let output = target.as_ref().map(|(_, name)| name.clone());
+        if output != self.overlay_output {
+            // Global tracking restarts on the new output and reports it anew.
+            self.overlay_output = output;
+            self.output_size = None;
+            self.global_cursor = None;
+        }
+        let close = self.overlay.take().map_or_else(Task::none, destroy_layer_surface);
+        let Some((output, _)) = target else {
+            return close;
+        };
+        let id = Id::unique();
+        self.overlay = Some(id);
+        let settings = overlay::surface_settings(id, &self.config, output);
+        Task::batch([close, get_layer_surface(settings)])
+    }
+
+    fn apply_config(&mut self, config: config::Config) {
+        if !config.global_tracking && self.mode != Mode::Overlay {
+            self.global_cursor = None;
+        }
+        self.eyes.set_config(&config);
+        if self.popup.is_none() {
+            self.set_draft(config.clone());
+        }
+        self.config = config;
+    }
+
+    fn save_window_size(&mut self) {
+        if !self.window_size_dirty {
             return;
         }
+        if let Err(err) = persist::save_window_size(self.window_size) {
+            tracing::warn!("failed to save the window size: {err}");
+        }
+        self.window_size_dirty = false;
+    }
 
-        let scale = self.core.scale_factor().max(0.1);
-        let global_logical = Point::new(global.x / scale, global.y / scale);
-        let new_offset = Vector::new(global_logical.x - local.x, global_logical.y - local.y);
+    fn set_draft(&mut self, draft: config::Config) {
+        self.preview.set_config(&draft);
+        self.draft = draft;
+    }
 
-        // Smooth to reduce jitter from timing mismatch.
-        self.global_to_local_offset = Some(if let Some(old) = self.global_to_local_offset {
-            let t = 0.25;
-            Vector::new(old.x + (new_offset.x - old.x) * t, old.y + (new_offset.y - old.y) * t)
-        } else {
-            new_offset
-        });
-        self.offset_dirty = true;
-
-        if std::env::var_os("COSMIC_EYES_DEBUG").is_some() {
-            tracing::info!(
-                scale,
-                global_x = global.x,
-                global_y = global.y,
-                global_logical_x = global_logical.x,
-                global_logical_y = global_logical.y,
-                local_x = local.x,
-                local_y = local.y,
-                offset_x = self.global_to_local_offset.unwrap().x,
-                offset_y = self.global_to_local_offset.unwrap().y,
-                "calibrated global->local offset"
-            );
+    /// Applies the draft right away and stores it, which also updates every other instance.
+    fn commit_draft(&mut self) {
+        if self.draft == self.config {
+            return;
+        }
+        if let Some(handler) = self.config_handler.as_ref() {
+            self.draft.save(handler);
         }
+        self.apply_config(self.draft.clone());
+    }
+
+    /// Where the panel configuration puts the applet on its output.
+    fn panel_origin(&self) -> Option<Point> {
+        let output = self.output_size?;
+        let config = self.panel_config.as_ref()?;
+        // Other applets are mostly icon buttons.
+        let (icon, _) = self.core.applet.suggested_size(true);
+        let padding = self.core.applet.suggested_padding(true);
+        let item_length = f32::from(icon) + 2.0 * f32::from(padding);
+        panel::applet_origin(config, APP_ID, output, self.window_size, item_length)
+    }
+
+    /// Until an offset is measured, works it out from where the panel configuration puts the
+    /// applet, so tracking works before the first hover.
+    fn estimate_offset_from_panel(&mut self) {
+        if self.mode == Mode::Applet {
+            let origin = self.panel_origin();
+            self.global_to_local_offset.estimate_from_panel(origin);
+        }
+    }
+
+    /// Forgets the calibration measurements, which no longer say where the eyes are: the panel
+    /// moved, the scale or the output changed, or the window may have moved.
+    fn reset_calibration(&mut self) {
+        self.global_to_local_offset.reset();
+    }
+
+    /// Starts calibrating afresh when the panel moved to another edge or the scale changed,
+    /// going by the panel configuration until the offset is measured again. The old offset
+    /// points at where the applet was, so without a panel estimate there is none until then.
+    fn check_placement(&mut self) {
+        let placement = (self.core.applet.anchor, self.core.scale_factor());
+        if self.placement.replace(placement).is_some_and(|old| old != placement) {
+            self.global_to_local_offset.forget();
+            self.estimate_offset_from_panel();
+        }
+    }
+}
+
+/// Logs calibration estimates when `COSMIC_EYES_DEBUG` is set.
+fn log_estimate(estimate: Option<eyes_core::Estimate>) {
+    let Some(estimate) = estimate else {
+        return;
+    };
+    if std::env::var_os("COSMIC_EYES_DEBUG").is_some() {
+        tracing::info!(
+            offset_x = estimate.offset.x,
+            offset_y = estimate.offset.y,
+            confidence = estimate.confidence,
+            inliers = estimate.inliers,
+            outliers = estimate.outliers,
+            "calibrated global->local offset"
+        );
     }
 }
@@ -0,0 +1,135 @@
+use cosmic::iced::{Point, Vector};
+use eyes_core::{Calibrator, Estimate};
+
+use std::time::Instant;
+
+/// Calibration confidence from which a measured offset replaces the saved one.
+const CONFIDENT_CALIBRATION: f32 = 0.75;
+
+/// The offset from global cursor positions to the applet's own, and how it is known: saved,
+/// worked out from the panel configuration, or measured while the cursor is over the eyes.
+#[derive(Debug, Default)]
+pub struct Offset {
+    value: Option<Vector>,
+    /// Measures `value` while the cursor is over the eyes.
+    calibrator: Calibrator,
+    /// A confident offset was measured since the offset was last saved.
+    dirty: bool,
+    /// The offset was measured, now or before, rather than worked out from the panel
+    /// configuration.
+    measured: bool,
+}
+
+impl Offset {
+    /// Starts from the offset saved for this output and applet instance, if any.
+    pub fn saved(saved: Option<Vector>) -> Self {
+        Self { value: saved, measured: saved.is_some(), ..Self::default() }
+    }
+
+    /// The offset in use, if any is known.
+    pub fn get(&self) -> Option<Vector> {
+        self.value
+    }
+
+    /// Adds a cursor position over the eyes; returns the new estimate, if any.
+    pub fn push_local(&mut self, at: Instant, position: Point) -> Option<Estimate> {
+        let estimate =
+            self.calibrator.push_local(at, eyes_core::Point::new(position.x, position.y));
+        self.calibrated(estimate)
+    }
+
+    /// Adds a global cursor position; returns the new estimate, if any.
+    pub fn push_global(&mut self, at: Instant, position: Point) -> Option<Estimate> {
+        let estimate =
+            self.calibrator.push_global(at, eyes_core::Point::new(position.x, position.y));
+        self.calibrated(estimate)
+    }
+
+    /// Takes a new calibration estimate: the offset is used right away if there was none, but
+    /// only replaces a known offset (and is saved) once it is confident.
+    fn calibrated(&mut self, estimate: Option<Estimate>) -> Option<Estimate> {
+        let estimate = estimate?;
+        let confident = estimate.confidence >= CONFIDENT_CALIBRATION;
+        if confident || self.value.is_none() {
+            self.value = Some(Vector::new(estimate.offset.x, estimate.offset.y));
+        }
+        self.dirty |= confident;
+        self.measured |= confident;
+        Some(estimate)
+    }
+
+    /// Until an offset is measured, goes by where the panel configuration puts the applet.
+    pub fn estimate_from_panel(&mut self, origin: Option<Point>) {
+        if self.measured {
+            return;
+        }
+        if let Some(origin) = origin {
+            self.value = Some(Vector::new(origin.x, origin.y));
+        }
+    }
+
+    /// Forgets the measurements, which no longer say where the eyes are, but keeps the offset
+    /// until a better one is known.
+    pub fn reset(&mut self) {
+        self.calibrator.reset();
+        self.measured = false;
+        self.dirty = false;
+    }
+
+    /// Forgets the offset too: the applet moved, so it points at the wrong place.
+    pub fn forget(&mut self) {
+        self.reset();
+        self.value = None;
+    }
+
+    /// The confidently measured offset, once, if it wasn't saved yet.
+    pub fn take_unsaved(&mut self) -> Option<Vector> {
+        std::mem::take(&mut self.dirty).then_some(self.value).flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(x: f32, confidence: f32) -> Option<Estimate> {
+        let offset = eyes_core::Vector::new(x, 0.0);
+        Some(Estimate { offset, confidence, inliers: 1, outliers: 0 })
+    }
+
+    #[test]
+    fn guesses_only_stand_in_for_an_unknown_offset() {
+        let mut offset = Offset::saved(Some(Vector::new(10.0, 0.0)));
+        offset.calibrated(estimate(20.0, 0.1));
+        assert_eq!(offset.get(), Some(Vector::new(10.0, 0.0)));
+        assert_eq!(offset.take_unsaved(), None);
+
+        offset.calibrated(estimate(30.0, 0.9));
+        assert_eq!(offset.get(), Some(Vector::new(30.0, 0.0)));
+        assert_eq!(offset.take_unsaved(), Some(Vector::new(30.0, 0.0)));
+        assert_eq!(offset.take_unsaved(), None);
+    }
+
+    #[test]
+    fn measured_offsets_win_over_the_panel_configuration() {
+        let mut offset = Offset::saved(Some(Vector::new(10.0, 0.0)));
+        offset.estimate_from_panel(Some(Point::new(50.0, 0.0)));
+        assert_eq!(offset.get(), Some(Vector::new(10.0, 0.0)));
+
+        offset.reset();
+        offset.estimate_from_panel(Some(Point::new(50.0, 0.0)));
+        assert_eq!(offset.get(), Some(Vector::new(50.0, 0.0)));
+    }
+
+    #[test]
+    fn a_moved_applet_without_a_panel_origin_recalibrates_from_scratch() {
+        let mut offset = Offset::saved(Some(Vector::new(10.0, 0.0)));
+        offset.forget();
+        offset.estimate_from_panel(None);
+        assert_eq!(offset.get(), None);
+
+        // With no offset to keep, the first guess is taken right away.
+        offset.calibrated(estimate(20.0, 0.1));
+        assert_eq!(offset.get(), Some(Vector::new(20.0, 0.0)));
+    }
+}
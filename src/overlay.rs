@@ -0,0 +1,103 @@
+use cosmic::cctk::wayland_client::protocol::wl_output::WlOutput;
+use cosmic::iced::platform_specific::runtime::wayland::layer_surface::{
+    IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+};
+use cosmic::iced::platform_specific::shell::commands::layer_surface::{
+    Anchor, KeyboardInteractivity, Layer,
+};
+use cosmic::iced::window::Id;
+use cosmic::iced::{Point, Size};
+
+use crate::config::{Config, OverlayAnchor, OverlayLayer};
+
+/// Where the overlay sits along one axis of its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    /// Left or top edge.
+    Start,
+    Middle,
+    /// Right or bottom edge.
+    End,
+}
+
+/// `(horizontal, vertical)` placement.
+fn sides(anchor: OverlayAnchor) -> (Side, Side) {
+    match anchor {
+        OverlayAnchor::TopLeft => (Side::Start, Side::Start),
+        OverlayAnchor::Top => (Side::Middle, Side::Start),
+        OverlayAnchor::TopRight => (Side::End, Side::Start),
+        OverlayAnchor::Left => (Side::Start, Side::Middle),
+        OverlayAnchor::Center => (Side::Middle, Side::Middle),
+        OverlayAnchor::Right => (Side::End, Side::Middle),
+        OverlayAnchor::BottomLeft => (Side::Start, Side::End),
+        OverlayAnchor::Bottom => (Side::Middle, Side::End),
+        OverlayAnchor::BottomRight => (Side::End, Side::End),
+    }
+}
+
+/// The layer surface for the overlay on `output`. It takes no keyboard focus and has an empty
+/// input region, so clicks always go to whatever is below it.
+pub fn surface_settings(id: Id, config: &Config, output: WlOutput) -> SctkLayerSurfaceSettings {
+    let (width, height) = config.overlay_size();
+    let (top, right, bottom, left) = config.overlay_margin;
+    let (horizontal, vertical) = sides(config.overlay_anchor);
+    let mut anchor = Anchor::empty();
+    match horizontal {
+        Side::Start => anchor |= Anchor::LEFT,
+        Side::End => anchor |= Anchor::RIGHT,
+        Side::Middle => {}
+    }
+    match vertical {
+        Side::Start => anchor |= Anchor::TOP,
+        Side::End => anchor |= Anchor::BOTTOM,
+        Side::Middle => {}
+    }
+
+    SctkLayerSurfaceSettings {
+        id,
+        layer: match config.overlay_layer {
+            OverlayLayer::Background => Layer::Background,
+            OverlayLayer::Bottom => Layer::Bottom,
+            OverlayLayer::Top => Layer::Top,
+            OverlayLayer::Overlay => Layer::Overlay,
+        },
+        keyboard_interactivity: KeyboardInteractivity::None,
+        pointer_interactivity: false,
+        anchor,
+        // A fixed output, not the active one: global tracking must measure on the same one.
+        output: IcedOutput::Output(output),
+        namespace: "cosmic-ext-eyes".to_string(),
+        margin: IcedMargin { top, right, bottom, left },
+        size: Some((Some(width), Some(height))),
+        // Placed against the whole output, not the area left by panels, so `origin` can
+        // tell where it is.
+        exclusive_zone: -1,
+        ..Default::default()
+    }
+}
+
+/// Whether going from `old` to `new` needs a new layer surface.
+pub fn surface_changed(old: &Config, new: &Config) -> bool {
+    old.overlay_layer != new.overlay_layer
+        || old.overlay_anchor != new.overlay_anchor
+        || old.overlay_size() != new.overlay_size()
+        || old.overlay_margin != new.overlay_margin
+        || old.overlay_output != new.overlay_output
+}
+
+/// Top-left corner of the overlay on an output of `output` logical size, where the compositor
+/// puts it: margins only count on anchored edges, and unanchored axes are centered.
+pub fn origin(config: &Config, output: Size) -> Point {
+    let (width, height) = config.overlay_size();
+    let (top, right, bottom, left) = config.overlay_margin;
+    let (horizontal, vertical) = sides(config.overlay_anchor);
+    let place = |side: Side, start: i32, end: i32, length: u32, total: f32| match side {
+        Side::Start => start as f32,
+        Side::Middle => (total - length as f32) / 2.0,
+        Side::End => total - length as f32 - end as f32,
+    };
+    Point::new(
+        place(horizontal, left, right, width, output.width),
+        place(vertical, top, bottom, height, output.height),
+    )
+}
@@ -0,0 +1,8 @@
+use eyes_core::{Color, Palette};
+
+/// The eye colors for the active COSMIC theme; `iris` overrides the accent-derived iris color.
+pub fn from_theme(theme: &cosmic::Theme, iris: Option<[u8; 3]>) -> Palette {
+    let cosmic = theme.cosmic();
+    let accent: cosmic::iced::Color = cosmic.accent_color().into();
+    Palette::new(cosmic.is_dark, Color::new(accent.r, accent.g, accent.b, accent.a), iris)
+}
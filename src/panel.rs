@@ -0,0 +1,212 @@
+use cosmic::applet::cosmic_panel_config::{CosmicPanelConfig, PanelAnchor, PanelType};
+use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::iced::{Point, Size, Subscription};
+
+/// Config id of the panel (or dock) the applet runs in.
+fn config_id(panel: &PanelType) -> String {
+    let name = match panel {
+        PanelType::Panel => "Panel",
+        PanelType::Dock => "Dock",
+        PanelType::Other(name) => name.as_str(),
+    };
+    format!("com.system76.CosmicPanel.{name}")
+}
+
+/// Loads the configuration of the panel (or dock) the applet runs in.
+pub fn config(panel: &PanelType) -> Option<CosmicPanelConfig> {
+    let id = config_id(panel);
+    let handler = match cosmic_config::Config::new(&id, CosmicPanelConfig::VERSION) {
+        Ok(handler) => handler,
+        Err(err) => {
+            tracing::warn!("failed to open panel config {id}: {err}");
+            return None;
+        }
+    };
+    // Missing keys fall back to the panel's defaults, as in the panel itself.
+    Some(CosmicPanelConfig::get_entry(&handler).unwrap_or_else(|(_, config)| config))
+}
+
+/// The panel's configuration whenever it changes, e.g. when its applets are reordered.
+pub fn watch_config(panel: &PanelType) -> Subscription<CosmicPanelConfig> {
+    cosmic_config::config_subscription::<_, CosmicPanelConfig>(
+        "cosmic-ext-eyes-panel-config",
+        config_id(panel).into(),
+        CosmicPanelConfig::VERSION,
+    )
+    .map(|update| update.config)
+}
+
+/// Where the applet `app_id` sits on its output (logical pixels), going by the panel's
+/// configuration: the anchor, gap, padding and spacing, and the applet's place in the panel's
+/// lists. `applet` is the applet's own size; every other applet is assumed to be
+/// `item_length` long, which holds for icon applets. `None` if the panel doesn't list the
+/// applet.
+///
+/// The panel doesn't report where it put the applets, so this is a best guess to track with
+/// until hovering measures the real position.
+pub fn applet_origin(
+    config: &CosmicPanelConfig,
+    app_id: &str,
+    output: Size,
+    applet: Size,
+    item_length: f32,
+) -> Option<Point> {
+    let horizontal = matches!(config.anchor, PanelAnchor::Top | PanelAnchor::Bottom);
+    let along = |size: Size| if horizontal { size.width } else { size.height };
+    let across = |size: Size| if horizontal { size.height } else { size.width };
+
+    let (start, end) = config.plugins_wings.clone().unwrap_or_default();
+    let center = config.plugins_center.clone().unwrap_or_default();
+    let sections = [start, center, end];
+    let (section, index) = sections.iter().enumerate().find_map(|(section, applets)| {
+        Some((section, applets.iter().position(|id| id == app_id)?))
+    })?;
+
+    let spacing = config.spacing as f32;
+    let padding = config.padding as f32;
+    let gap = if config.anchor_gap { config.margin as f32 } else { 0.0 };
+    // Length of a section's applets with the spacing between them.
+    let length = |s: usize| {
+        let applets = &sections[s];
+        let items: f32 = (0..applets.len())
+            .map(|i| if (s, i) == (section, index) { along(applet) } else { item_length })
+            .sum();
+        items + spacing * applets.len().saturating_sub(1) as f32
+    };
+    let [start_length, center_length, end_length] = [0, 1, 2].map(length);
+
+    // Expanded panels span the output; the others shrink to their content and are centered.
+    let panel_length = if config.expand_to_edges {
+        along(output) - 2.0 * gap
+    } else {
+        let lengths = [start_length, center_length, end_length];
+        let gaps = lengths.iter().filter(|&&length| length > 0.0).count().saturating_sub(1);
+        lengths.iter().sum::<f32>() + spacing * gaps as f32 + 2.0 * padding
+    };
+    let panel_start = (along(output) - panel_length) / 2.0;
+    let content_start = panel_start + padding;
+    let content_end = panel_start + panel_length - padding;
+    let section_start = match section {
+        0 => content_start,
+        1 if config.expand_to_edges => panel_start + (panel_length - center_length) / 2.0,
+        1 => content_start + start_length + if start_length > 0.0 { spacing } else { 0.0 },
+        _ => content_end - end_length,
+    };
+    let before: f32 = (0..index).map(|_| item_length + spacing).sum();
+    let along_position = section_start + before;
+
+    let across_position = match config.anchor {
+        PanelAnchor::Top | PanelAnchor::Left => gap + padding,
+        PanelAnchor::Bottom | PanelAnchor::Right => {
+            across(output) - gap - padding - across(applet)
+        }
+    };
+    Some(if horizontal {
+        Point::new(along_position, across_position)
+    } else {
+        Point::new(across_position, along_position)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: Size = Size::new(1920.0, 1080.0);
+    /// Other applets are this long; the eyes are longer.
+    const ITEM: f32 = 40.0;
+
+    fn config(
+        anchor: PanelAnchor,
+        start: &[&str],
+        center: &[&str],
+        end: &[&str],
+    ) -> CosmicPanelConfig {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        CosmicPanelConfig {
+            anchor,
+            anchor_gap: false,
+            margin: 4,
+            padding: 4,
+            spacing: 2,
+            expand_to_edges: true,
+            plugins_wings: Some((ids(start), ids(end))),
+            plugins_center: Some(ids(center)),
+            ..CosmicPanelConfig::default()
+        }
+    }
+
+    fn origin(config: &CosmicPanelConfig) -> Option<Point> {
+        let applet = match config.anchor {
+            PanelAnchor::Top | PanelAnchor::Bottom => Size::new(60.0, 32.0),
+            PanelAnchor::Left | PanelAnchor::Right => Size::new(32.0, 60.0),
+        };
+        applet_origin(config, "eyes", OUTPUT, applet, ITEM)
+    }
+
+    #[test]
+    fn every_anchor_puts_the_applet_against_its_edge() {
+        let start = ["a", "eyes"];
+        // Padding, then one applet and the spacing before the eyes.
+        let along = 4.0 + ITEM + 2.0;
+        let cases = [
+            (PanelAnchor::Top, Point::new(along, 4.0)),
+            (PanelAnchor::Bottom, Point::new(along, 1080.0 - 4.0 - 32.0)),
+            (PanelAnchor::Left, Point::new(4.0, along)),
+            (PanelAnchor::Right, Point::new(1920.0 - 4.0 - 32.0, along)),
+        ];
+        for (anchor, expected) in cases {
+            assert_eq!(origin(&config(anchor, &start, &[], &[])), Some(expected), "{anchor:?}");
+        }
+    }
+
+    #[test]
+    fn expanded_panels_spread_the_sections_over_the_output() {
+        let top = PanelAnchor::Top;
+        // The end section ends at the padding: 1916 - (40 + 2 + 60), then past one applet.
+        let end = config(top, &["a"], &["b"], &["c", "eyes"]);
+        assert_eq!(origin(&end), Some(Point::new(1814.0 + ITEM + 2.0, 4.0)));
+        // The center section is centered on the output: (1920 - (40 + 2 + 60 + 2 + 40)) / 2.
+        let center = config(top, &["a"], &["b", "eyes", "c"], &[]);
+        assert_eq!(origin(&center), Some(Point::new(888.0 + ITEM + 2.0, 4.0)));
+    }
+
+    #[test]
+    fn shrunk_panels_are_centered_around_their_applets() {
+        let top = PanelAnchor::Top;
+        // 40 + 2 + 60 + 2 + 40 long with the spacing between sections, plus the padding.
+        let mut center = config(top, &["a"], &["eyes"], &["b"]);
+        center.expand_to_edges = false;
+        let panel_start = (1920.0 - 152.0) / 2.0;
+        assert_eq!(origin(&center), Some(Point::new(panel_start + 4.0 + ITEM + 2.0, 4.0)));
+
+        let mut start = config(top, &["eyes"], &[], &[]);
+        start.expand_to_edges = false;
+        assert_eq!(origin(&start), Some(Point::new((1920.0 - 68.0) / 2.0 + 4.0, 4.0)));
+
+        let mut end = config(top, &[], &[], &["a", "eyes"]);
+        end.expand_to_edges = false;
+        let panel_start = (1920.0 - 110.0) / 2.0;
+        assert_eq!(origin(&end), Some(Point::new(panel_start + 4.0 + ITEM + 2.0, 4.0)));
+    }
+
+    #[test]
+    fn anchor_gaps_keep_the_panel_off_the_edges() {
+        let mut top = config(PanelAnchor::Top, &["eyes"], &[], &[]);
+        top.anchor_gap = true;
+        assert_eq!(origin(&top), Some(Point::new(8.0, 8.0)));
+
+        let mut bottom = config(PanelAnchor::Bottom, &[], &[], &["eyes"]);
+        bottom.anchor_gap = true;
+        assert_eq!(origin(&bottom), Some(Point::new(1920.0 - 8.0 - 60.0, 1080.0 - 8.0 - 32.0)));
+    }
+
+    #[test]
+    fn unlisted_applets_have_no_origin() {
+        assert_eq!(origin(&config(PanelAnchor::Top, &["a"], &["b"], &["c"])), None);
+        let mut unset = config(PanelAnchor::Top, &[], &[], &[]);
+        unset.plugins_wings = None;
+        unset.plugins_center = None;
+        assert_eq!(origin(&unset), None);
+    }
+}
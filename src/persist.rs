@@ -1,7 +1,34 @@
-use cosmic::iced::Vector;
+use cosmic::iced::{Size, Vector};
 use std::path::{Path, PathBuf};
 
-fn sanitize_key(raw: &str) -> String {
+/// A saved global-to-local offset: the scale factor it was measured at and the offset itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    pub scale: f32,
+    pub offset: Vector,
+}
+
+impl Calibration {
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split_whitespace();
+        let scale = parts.next()?.parse::<f32>().ok()?;
+        let x = parts.next()?.parse::<f32>().ok()?;
+        let y = parts.next()?.parse::<f32>().ok()?;
+        Some(Self { scale, offset: Vector::new(x, y) })
+    }
+
+    /// Reads a calibration file; `None` if it is missing or unreadable.
+    pub fn read(path: &Path) -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(path).ok()?)
+    }
+
+    /// Offsets only apply at the scale factor they were measured at.
+    pub fn applies_at(&self, scale: f32) -> bool {
+        (self.scale - scale).abs() <= 0.01
+    }
+}
+
+pub fn sanitize_key(raw: &str) -> String {
     let raw = raw.trim();
     if raw.is_empty() {
         return "default".to_string();
@@ -11,12 +38,15 @@ fn sanitize_key(raw: &str) -> String {
         .collect()
 }
 
-fn output_key() -> String {
+/// The panel output this instance calibrates for.
+pub fn output_key() -> String {
     let raw = std::env::var("COSMIC_PANEL_OUTPUT").unwrap_or_else(|_| "default".to_string());
     sanitize_key(&raw)
 }
 
-fn instance_key() -> String {
+/// The applet instance on that output; lets a panel and a dock on the same output keep
+/// separate offsets.
+pub fn instance_key() -> String {
     if let Ok(raw) = std::env::var("COSMIC_EYES_OFFSET_KEY") {
         return sanitize_key(&raw);
     }
@@ -58,47 +88,187 @@ fn state_dir() -> Option<PathBuf> {
     Some(Path::new(&home).join(".local/state"))
 }
 
-fn offset_path() -> Option<PathBuf> {
-    Some(
-        state_dir()?
-            .join("cosmic-ext-eyes")
-            .join(format!("offset-{}-{}.txt", output_key(), instance_key())),
-    )
+/// Where the state files live.
+pub fn app_state_dir() -> Option<PathBuf> {
+    Some(state_dir()?.join("cosmic-ext-eyes"))
 }
 
-fn legacy_offset_path() -> Option<PathBuf> {
-    Some(
-        state_dir()?
-            .join("cosmic-ext-eyes")
-            .join(format!("offset-{}.txt", output_key())),
-    )
+/// Separates the output and instance keys in calibration file names. [`sanitize_key`] never
+/// produces it, so a name splits back into exactly the keys it was made from.
+const KEY_SEPARATOR: char = '@';
+
+/// The calibration file for an output and instance key (both already sanitized).
+pub fn offset_path_for(output: &str, instance: &str) -> Option<PathBuf> {
+    Some(app_state_dir()?.join(format!("offset-{output}{KEY_SEPARATOR}{instance}.txt")))
 }
 
-pub fn load_offset(current_scale: f32) -> Option<Vector> {
-    let bytes = match std::fs::read_to_string(offset_path()?) {
-        Ok(bytes) => bytes,
-        Err(_) => std::fs::read_to_string(legacy_offset_path()?).ok()?,
+/// The calibration file of the current environment.
+pub fn offset_path() -> Option<PathBuf> {
+    offset_path_for(&output_key(), &instance_key())
+}
+
+/// The name the current environment's file had while the keys were joined with a dash; still
+/// read, and removed when the offset is next saved.
+pub fn dashed_offset_path() -> Option<PathBuf> {
+    Some(app_state_dir()?.join(format!("offset-{}-{}.txt", output_key(), instance_key())))
+}
+
+/// Per-output file from before offsets were kept per instance; still read as a fallback.
+pub fn legacy_offset_path() -> Option<PathBuf> {
+    Some(app_state_dir()?.join(format!("offset-{}.txt", output_key())))
+}
+
+/// Every calibration file in the state directory, sorted by name.
+pub fn offset_files() -> std::io::Result<Vec<PathBuf>> {
+    let Some(dir) = app_state_dir() else {
+        return Ok(Vec::new());
     };
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if offset_key(&path).is_some() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
 
-    let mut parts = bytes.split_whitespace();
-    let saved_scale = parts.next()?.parse::<f32>().ok()?;
-    let x = parts.next()?.parse::<f32>().ok()?;
-    let y = parts.next()?.parse::<f32>().ok()?;
+/// The `<output>@<instance>` part of a calibration file name, or `<output>-<instance>` and
+/// `<output>` in older ones; match it with [`key_matches`].
+pub fn offset_key(path: &Path) -> Option<&str> {
+    path.file_name()?.to_str()?.strip_prefix("offset-")?.strip_suffix(".txt")
+}
+
+/// Whether a calibration file `key` belongs to `output` and `instance` (sanitized keys); `None`
+/// matches any.
+///
+/// Older names joined the keys with a dash, which both keys may contain too (`DP-1`,
+/// `Panel-Top`), so they can't be split; they only match when both keys are given and spell
+/// out the name exactly, or when the output alone names a per-output file.
+pub fn key_matches(key: &str, output: Option<&str>, instance: Option<&str>) -> bool {
+    if let Some((key_output, key_instance)) = key.split_once(KEY_SEPARATOR) {
+        return output.is_none_or(|output| output == key_output)
+            && instance.is_none_or(|instance| instance == key_instance);
+    }
+    match (output, instance) {
+        (None, None) => true,
+        (Some(output), None) => key == output,
+        (None, Some(_)) => false,
+        (Some(output), Some(instance)) => key == format!("{output}-{instance}"),
+    }
+}
+
+/// The calibration the current environment uses, from its own file or an older one, and the
+/// file it came from.
+pub fn current_calibration() -> Option<(PathBuf, Calibration)> {
+    [offset_path(), dashed_offset_path(), legacy_offset_path()]
+        .into_iter()
+        .flatten()
+        .find_map(|path| Calibration::read(&path).map(|calibration| (path, calibration)))
+}
 
-    if (saved_scale - current_scale).abs() > 0.01 {
+pub fn load_offset(current_scale: f32) -> Option<Vector> {
+    let (_, calibration) = current_calibration()?;
+    if !calibration.applies_at(current_scale) {
         return None;
     }
 
-    Some(Vector::new(x, y))
+    Some(calibration.offset)
 }
 
 pub fn save_offset(current_scale: f32, offset: Vector) -> std::io::Result<()> {
     let Some(path) = offset_path() else {
         return Ok(());
     };
+    save_offset_to(&path, current_scale, offset)?;
+    // Superseded by the file just written.
+    if let Some(dashed) = dashed_offset_path() {
+        match std::fs::remove_file(dashed) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub fn save_offset_to(path: &Path, scale: f32, offset: Vector) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, format!("{} {} {}\n", scale, offset.x, offset.y))
+}
+
+fn window_size_path() -> Option<PathBuf> {
+    Some(app_state_dir()?.join("window-size.txt"))
+}
+
+/// Last size of the standalone window (`--window`), in logical pixels.
+pub fn load_window_size() -> Option<Size> {
+    let bytes = std::fs::read_to_string(window_size_path()?).ok()?;
+    let mut parts = bytes.split_whitespace();
+    let width = parts.next()?.parse::<f32>().ok()?;
+    let height = parts.next()?.parse::<f32>().ok()?;
+    (width >= 1.0 && height >= 1.0).then(|| Size::new(width, height))
+}
+
+pub fn save_window_size(size: Size) -> std::io::Result<()> {
+    let Some(path) = window_size_path() else {
+        return Ok(());
+    };
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    std::fs::write(path, format!("{} {} {}\n", current_scale, offset.x, offset.y))
+    std::fs::write(path, format!("{} {}\n", size.width, size.height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_match_despite_dashes_in_output_names() {
+        assert!(key_matches("DP-1@Panel", Some("DP-1"), None));
+        assert!(key_matches("DP-1@Panel", None, Some("Panel")));
+        assert!(key_matches("DP-1@Panel", Some("DP-1"), Some("Panel")));
+        assert!(!key_matches("DP-10@Panel", Some("DP-1"), None));
+        assert!(!key_matches("DP-1@Panel", Some("DP-1"), Some("Dock")));
+        assert!(key_matches("anything", None, None));
+    }
+
+    #[test]
+    fn keys_only_match_whole_keys() {
+        assert!(!key_matches("DP-1@Panel-Top", Some("DP"), None));
+        assert!(!key_matches("DP-2@Dock-Top", Some("DP"), None));
+        assert!(!key_matches("DP-1@Panel-Top", None, Some("Top")));
+        assert!(!key_matches("DP-1@Panel-Top", None, Some("Panel")));
+        assert!(key_matches("DP-1@Panel-Top", None, Some("Panel-Top")));
+    }
+
+    #[test]
+    fn dashed_names_only_match_exactly() {
+        assert!(key_matches("DP-1-Panel-Top", Some("DP-1"), Some("Panel-Top")));
+        assert!(!key_matches("DP-1-Panel-Top", Some("DP-1"), None));
+        assert!(!key_matches("DP-1-Panel-Top", Some("DP"), None));
+        assert!(!key_matches("DP-1-Panel-Top", None, Some("Top")));
+        assert!(!key_matches("DP-1-Panel-Top", Some("DP"), Some("1-Panel")));
+        // The per-output file from before instances.
+        assert!(key_matches("DP-1", Some("DP-1"), None));
+        assert!(!key_matches("DP-1", Some("DP"), None));
+    }
+
+    #[test]
+    fn calibration_round_trips_through_its_file_format() {
+        let calibration = Calibration::parse("1.25 -12.5 40\n").unwrap();
+        assert_eq!(calibration.scale, 1.25);
+        assert_eq!(calibration.offset, Vector::new(-12.5, 40.0));
+        assert_eq!(Calibration::parse("1.25 -12.5"), None);
+    }
 }
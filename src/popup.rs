@@ -0,0 +1,90 @@
+use cosmic::app::Core;
+use cosmic::iced::{Length, Size};
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+use eyes_core::EyeShape;
+
+use crate::config::{Config, Gaze, Style};
+use crate::eyes::Eyes;
+use crate::Message;
+
+/// Fixed so the preview's `Eyes::tick` and its canvas agree on the layout.
+pub const PREVIEW_SIZE: Size = Size::new(160.0, 72.0);
+
+const STYLES: [Style; 2] = [Style::Classic, Style::Flat];
+const STYLE_NAMES: [&str; 2] = ["Classic", "Flat"];
+
+const SHAPES: [EyeShape; 4] =
+    [EyeShape::Circle, EyeShape::Ellipse, EyeShape::Almond, EyeShape::RoundedRect];
+const SHAPE_NAMES: [&str; 4] = ["Round", "Tall", "Almond", "Rounded square"];
+
+pub fn style_at(index: usize) -> Option<Style> {
+    STYLES.get(index).copied()
+}
+
+pub fn shape_at(index: usize) -> Option<EyeShape> {
+    SHAPES.get(index).copied()
+}
+
+/// Settings popup: a live preview of `draft` plus the controls that edit it.
+pub fn view<'a>(core: &'a Core, draft: &'a Config, preview: &'a Eyes) -> Element<'a, Message> {
+    let preview = widget::container(
+        cosmic::iced::widget::mouse_area(
+            widget::container(preview.view())
+                .width(Length::Fixed(PREVIEW_SIZE.width))
+                .height(Length::Fixed(PREVIEW_SIZE.height)),
+        )
+        .on_move(|position| Message::PreviewCursor(Some(position)))
+        .on_exit(Message::PreviewCursor(None)),
+    )
+    .center_x(Length::Fill)
+    .padding(8);
+
+    let style_index = STYLES.iter().position(|style| *style == draft.style);
+    let shape_index = SHAPES.iter().position(|shape| *shape == draft.shape);
+
+    let content = widget::list_column()
+        .padding(5)
+        .spacing(0)
+        .add(preview)
+        .add(settings::item(
+            "Eye size",
+            widget::slider(12.0..=64.0, draft.max_eye_radius, Message::SetEyeSize)
+                .step(1.0)
+                .on_release(Message::CommitSettings),
+        ))
+        .add(settings::item(
+            "Pupil size",
+            widget::slider(0.2..=0.6, draft.pupil_ratio, Message::SetPupilRatio)
+                .step(0.01)
+                .on_release(Message::CommitSettings),
+        ))
+        .add(settings::item(
+            "Follow speed",
+            widget::slider(2.0..=30.0, draft.follow_speed, Message::SetFollowSpeed)
+                .step(0.5)
+                .on_release(Message::CommitSettings),
+        ))
+        .add(settings::item(
+            "Follow the cursor everywhere",
+            widget::toggler(draft.global_tracking).on_toggle(Message::SetGlobalTracking),
+        ))
+        .add(settings::item(
+            "Iris",
+            widget::toggler(draft.iris).on_toggle(Message::SetIris),
+        ))
+        .add(settings::item(
+            "3D eyeballs",
+            widget::toggler(draft.gaze == Gaze::Eyeball).on_toggle(Message::SetEyeball),
+        ))
+        .add(settings::item(
+            "Style",
+            widget::dropdown(&STYLE_NAMES, style_index, Message::SetStyle),
+        ))
+        .add(settings::item(
+            "Shape",
+            widget::dropdown(&SHAPE_NAMES, shape_index, Message::SetShape),
+        ));
+
+    core.applet.popup_container(content).into()
+}